@@ -0,0 +1,155 @@
+use std::{cell::RefCell, collections::{HashMap, HashSet}, rc::Rc};
+
+use crate::{error::TensorError, tensor::Tensor};
+
+// The operation that produced a variable, keeping its inputs alive for the backward pass
+enum Op {
+    Leaf,
+    Add(Variable, Variable),
+    Mul(Variable, Variable),
+    MatMul(Variable, Variable),
+    Sum(Variable)
+}
+
+struct Node {
+    value: Tensor<f64>,
+    grad: RefCell<Option<Tensor<f64>>>,
+    op: Op
+}
+
+/* A tensor that records the operations applied to it, so that backward() can work
+   out the gradient of the result with respect to every leaf it was computed from */
+#[derive(Clone)]
+pub struct Variable {
+    node: Rc<Node>
+}
+
+// Sums a gradient back down to the shape of an input that was broadcast in the forward pass
+fn unbroadcast(grad: &Tensor<f64>, shape: &[usize]) -> Result<Tensor<f64>, TensorError> {
+    let extra = grad.rank() - shape.len();
+    let axes: Vec<usize> = (0..grad.rank())
+        .filter(|&d| d < extra || (shape[d - extra] == 1 && grad.shape()[d] != 1))
+        .collect();
+    grad.sum_axes(&axes, true)?.reshape(shape)
+}
+
+fn send(grads: &mut HashMap<*const Node, Tensor<f64>>, to: &Variable, grad: Tensor<f64>) -> Result<(), TensorError> {
+    let total = match grads.remove(&Rc::as_ptr(&to.node)) {
+        Some(existing) => existing.add(&grad)?,
+        None => grad
+    };
+    grads.insert(Rc::as_ptr(&to.node), total);
+    Ok(())
+}
+
+impl Variable {
+    pub fn new(value: Tensor<f64>) -> Variable {
+        Self::from_op(value, Op::Leaf)
+    }
+
+    fn from_op(value: Tensor<f64>, op: Op) -> Variable {
+        Variable {
+            node: Rc::new(Node {
+                value,
+                grad: RefCell::new(None),
+                op
+            })
+        }
+    }
+
+    pub fn value(&self) -> Tensor<f64> {
+        self.node.value.clone()
+    }
+
+    // Only leaf variables keep a gradient, and it accumulates over backward() calls
+    pub fn grad(&self) -> Option<Tensor<f64>> {
+        self.node.grad.borrow().clone()
+    }
+
+    pub fn zero_grad(&self) {
+        *self.node.grad.borrow_mut() = None;
+    }
+
+    pub fn add(&self, other: &Variable) -> Result<Variable, TensorError> {
+        let value = self.node.value.add(&other.node.value)?;
+        Ok(Self::from_op(value, Op::Add(self.clone(), other.clone())))
+    }
+
+    pub fn mul(&self, other: &Variable) -> Result<Variable, TensorError> {
+        let value = self.node.value.mul(&other.node.value)?;
+        Ok(Self::from_op(value, Op::Mul(self.clone(), other.clone())))
+    }
+
+    pub fn matmul(&self, other: &Variable) -> Result<Variable, TensorError> {
+        // The backward pass below only handles plain matrices, not batches
+        if self.node.value.rank() != 2 || other.node.value.rank() != 2 {
+            return Err(TensorError::new("Variable::matmul requires rank-2 tensors"));
+        }
+        let value = self.node.value.matmul(&other.node.value)?;
+        Ok(Self::from_op(value, Op::MatMul(self.clone(), other.clone())))
+    }
+
+    pub fn sum(&self) -> Variable {
+        Self::from_op(Tensor::scalar(self.node.value.sum()), Op::Sum(self.clone()))
+    }
+
+    // Every variable this one depends on, each placed before all of its inputs
+    fn topological_order(&self) -> Vec<Variable> {
+        fn visit(v: &Variable, seen: &mut HashSet<*const Node>, order: &mut Vec<Variable>) {
+            if !seen.insert(Rc::as_ptr(&v.node)) {
+                return;
+            }
+            match &v.node.op {
+                Op::Leaf => {},
+                Op::Add(a, b) | Op::Mul(a, b) | Op::MatMul(a, b) => {
+                    visit(a, seen, order);
+                    visit(b, seen, order);
+                },
+                Op::Sum(a) => visit(a, seen, order)
+            }
+            order.push(v.clone());
+        }
+        let mut order = Vec::new();
+        visit(self, &mut HashSet::new(), &mut order);
+        order.reverse();
+        order
+    }
+
+    /* Reverse-mode accumulation from this variable. A non-scalar output is treated
+       as if it had been summed first, i.e. it is seeded with a gradient of ones */
+    pub fn backward(&self) -> Result<(), TensorError> {
+        let mut grads = HashMap::new();
+        grads.insert(Rc::as_ptr(&self.node), Tensor::ones(self.node.value.shape()));
+        for v in self.topological_order() {
+            let Some(grad) = grads.remove(&Rc::as_ptr(&v.node)) else {
+                continue;
+            };
+            match &v.node.op {
+                Op::Leaf => {
+                    let mut slot = v.node.grad.borrow_mut();
+                    let total = match slot.take() {
+                        Some(existing) => existing.add(&grad)?,
+                        None => grad
+                    };
+                    *slot = Some(total);
+                },
+                Op::Add(a, b) => {
+                    send(&mut grads, a, unbroadcast(&grad, a.node.value.shape())?)?;
+                    send(&mut grads, b, unbroadcast(&grad, b.node.value.shape())?)?;
+                },
+                Op::Mul(a, b) => {
+                    send(&mut grads, a, unbroadcast(&grad.mul(&b.node.value)?, a.node.value.shape())?)?;
+                    send(&mut grads, b, unbroadcast(&grad.mul(&a.node.value)?, b.node.value.shape())?)?;
+                },
+                Op::MatMul(a, b) => {
+                    send(&mut grads, a, grad.matmul(&b.node.value.transpose(0, 1)?)?)?;
+                    send(&mut grads, b, a.node.value.transpose(0, 1)?.matmul(&grad)?)?;
+                },
+                Op::Sum(a) => {
+                    send(&mut grads, a, grad.broadcast_to(a.node.value.shape())?.contiguous())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
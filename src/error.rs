@@ -1,28 +1,44 @@
-use std::fmt::{Debug, Display};
+use std::{error::Error, fmt::{Debug, Display}};
 
 // Super basic error struct, can make better later (famous last words)
 pub struct TensorError {
-    pub message: String
+    pub message: String,
+    // The IO, parse, etc. error this one was caused by, if any
+    pub source: Option<Box<dyn Error + Send + Sync>>
 }
 
 impl TensorError {
     pub fn new<M: Into<String>>(message: M) -> TensorError {
         TensorError {
-            message: message.into()
+            message: message.into(),
+            source: None
         }
     }
+
+    pub fn with_source<E: Into<Box<dyn Error + Send + Sync>>>(mut self, source: E) -> TensorError {
+        self.source = Some(source.into());
+        self
+    }
 }
 
 impl Display for TensorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.message)?;
+        if let Some(source) = &self.source {
+            write!(f, ": {}", source)?;
+        }
         Ok(())
     }
 }
 
 impl Debug for TensorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)?;
-        Ok(())
+        write!(f, "{}", self)
     }
-}
\ No newline at end of file
+}
+
+impl Error for TensorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
@@ -0,0 +1,58 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::tensor::Tensor;
+
+/* A lazily evaluated chain of element-wise operations on a tensor. Each step only
+   records a closure; eval() then runs the whole chain per element in one pass, so
+   e.g. Expr::new(&t).mul_scalar(2.0).add_scalar(1.0).relu().eval() allocates a
+   single result instead of one intermediate tensor per step */
+pub struct Expr<'a, T> {
+    source: Tensor<T>,
+    ops: Vec<Box<dyn Fn(T) -> T + 'a>>
+}
+
+impl<'a, T: Clone + 'a> Expr<'a, T> {
+    pub fn new(source: &Tensor<T>) -> Expr<'a, T> {
+        Expr {
+            source: source.clone(),
+            ops: Vec::new()
+        }
+    }
+
+    pub fn map<F: Fn(T) -> T + 'a>(mut self, f: F) -> Expr<'a, T> {
+        self.ops.push(Box::new(f));
+        self
+    }
+
+    pub fn add_scalar(self, s: T) -> Expr<'a, T> where T: Add<Output=T> {
+        self.map(move |x| x + s.clone())
+    }
+
+    pub fn sub_scalar(self, s: T) -> Expr<'a, T> where T: Sub<Output=T> {
+        self.map(move |x| x - s.clone())
+    }
+
+    pub fn mul_scalar(self, s: T) -> Expr<'a, T> where T: Mul<Output=T> {
+        self.map(move |x| x * s.clone())
+    }
+
+    pub fn div_scalar(self, s: T) -> Expr<'a, T> where T: Div<Output=T> {
+        self.map(move |x| x / s.clone())
+    }
+
+    pub fn relu(self) -> Expr<'a, T> where T: PartialOrd + Default {
+        self.map(|x| if x > T::default() { x } else { T::default() })
+    }
+
+    pub fn eval(&self) -> Tensor<T> {
+        self.source.map(|x| self.ops.iter().fold(x, |acc, op| op(acc)))
+    }
+}
+
+impl<'a, T: Clone + Neg<Output=T> + 'a> Neg for Expr<'a, T> {
+    type Output = Expr<'a, T>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|x| -x)
+    }
+}
@@ -0,0 +1,19 @@
+pub mod autograd;
+pub mod error;
+pub mod expr;
+pub mod numeric;
+pub mod tensor;
+pub mod testing;
+
+pub use error::TensorError;
+pub use numeric::Numeric;
+pub use tensor::{Axis, Tensor};
+#[cfg(feature = "simd")]
+pub use wide::f32x8;
+
+// Everything most users need, via `use crabtorch::prelude::*`
+pub mod prelude {
+    pub use crate::error::TensorError;
+    pub use crate::numeric::Numeric;
+    pub use crate::tensor::{Axis, Tensor};
+}
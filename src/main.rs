@@ -1,8 +1,4 @@
-mod tensor;
-mod error;
-
-use error::TensorError;
-use tensor::Tensor;
+use crabtorch::prelude::*;
 
 fn main() -> Result<(), TensorError> {
     let t = Tensor::from_iter(0..100).reshape(&[50, 2])?; 
@@ -0,0 +1,30 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+/* Shorthand for the bounds the arithmetic methods keep asking for. There is nothing
+   to implement: every primitive number type, and any type of your own with these
+   operations, is Numeric automatically through the blanket impl below */
+pub trait Numeric:
+    Clone + Default + PartialOrd + From<bool>
+    + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self>
+{
+    // Builds n out of ones by binary expansion, since From<bool> is all the primitives share
+    fn from_usize(mut n: usize) -> Self {
+        let mut result = Self::from(false);
+        let mut bit = Self::from(true);
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result + bit.clone();
+            }
+            n >>= 1;
+            if n > 0 {
+                bit = bit.clone() + bit;
+            }
+        }
+        result
+    }
+}
+
+impl<T> Numeric for T
+where T: Clone + Default + PartialOrd + From<bool>
+    + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T>
+{}
@@ -1,10 +1,24 @@
-use std::{cell::RefCell, fmt::Display, ops::{Add, Div, Mul, Sub}, rc::Rc};
+use std::{cell::RefCell, cmp::Ordering, fmt::{Debug, Display}, iter::{Product, Sum}, marker::PhantomData, ops::{Add, Range, BitAnd, BitOr, BitXor, Div, Mul, Neg, Rem, Shl, Shr, Sub}, rc::Rc};
 
-use rand::{distributions::Standard, prelude::Distribution, Rng};
+use rand::{distributions::Standard, prelude::Distribution, rngs::StdRng, Rng, SeedableRng};
 
-use crate::error::TensorError;
+#[cfg(feature = "complex")]
+use num_complex::Complex;
+#[cfg(feature = "csv")]
+use std::path::Path;
+#[cfg(feature = "simd")]
+use wide::f32x8;
+
+use crate::{error::TensorError, numeric::Numeric};
+
+// A debug event naming the operation and its shapes; compiles to nothing without the trace feature
+macro_rules! trace_op {
+    ($op:expr, $inputs:expr, $output:expr) => {
+        #[cfg(feature = "trace")]
+        tracing::debug!(op = $op, inputs = ?$inputs, output = ?$output);
+    };
+}
 
-#[derive(Clone, Debug)]
 pub struct Tensor<T> {
     data: Rc<RefCell<Vec<T>>>,
     base_index: usize,
@@ -13,24 +27,205 @@ pub struct Tensor<T> {
     strides: Vec<usize>
 }
 
+/* Marks a usize as an axis rather than a count, e.g. sum_axis(Axis(1)). Every method
+   taking one also accepts a bare usize, so sum_axis(1) means the same thing */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Axis(pub usize);
+
+impl From<usize> for Axis {
+    fn from(axis: usize) -> Axis {
+        Axis(axis)
+    }
+}
+
+// A snapshot of how a tensor's elements are laid out in its buffer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub shape: Vec<usize>,
+    pub strides: Vec<usize>,
+    pub base_index: usize
+}
+
+// Cloning only shares the buffer, so it shouldn't need T: Clone
+impl<T> Clone for Tensor<T> {
+    fn clone(&self) -> Self {
+        Tensor {
+            data: self.data.clone(),
+            base_index: self.base_index,
+            size: self.size,
+            shape: self.shape.clone(),
+            strides: self.strides.clone()
+        }
+    }
+}
+
+// An empty rank-1 tensor
+impl<T> Default for Tensor<T> {
+    fn default() -> Self {
+        Tensor::from_data(Vec::new(), &[0])
+    }
+}
+
 fn get_size_and_strides(shape: &[usize]) -> (usize, Vec<usize>) {
-    let mut strides = vec![0 as usize; shape.len()];
+    let mut strides = vec![0; shape.len()];
     let mut size: usize = 1;
     for i in (0..shape.len()).rev() {
         strides[i] = size;
         size *= shape[i];
     }
-    return (size, strides);
+    (size, strides)
+}
+
+// One side of a rearrange pattern, e.g. "(h w) c" is [["h", "w"], ["c"]]
+fn parse_axis_groups(spec: &str) -> Result<Vec<Vec<&str>>, TensorError> {
+    let error = || TensorError::new(format!("cannot parse axis list {:?}", spec.trim()));
+    let mut groups = Vec::new();
+    let mut open: Option<Vec<&str>> = None;
+    let mut rest = spec;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c == '(' {
+            if open.is_some() {
+                return Err(error());
+            }
+            open = Some(Vec::new());
+            rest = &rest[1..];
+        } else if c == ')' {
+            groups.push(open.take().ok_or_else(error)?);
+            rest = &rest[1..];
+        } else if c.is_alphanumeric() || c == '_' {
+            let end = rest.find(|c: char| !(c.is_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            match &mut open {
+                Some(group) => group.push(&rest[..end]),
+                None => groups.push(vec![&rest[..end]])
+            }
+            rest = &rest[end..];
+        } else {
+            return Err(error());
+        }
+    }
+    if open.is_some() {
+        return Err(error());
+    }
+    Ok(groups)
+}
+
+// Right-aligns both shapes and stretches size-1 dimensions, NumPy style
+fn try_broadcast_shapes(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let rank = a.len().max(b.len());
+    let pad = |s: &[usize]| [vec![1; rank - s.len()], s.to_vec()].concat();
+    pad(a).iter().zip(pad(b)).map(|(&x, y)| {
+        if x == y || y == 1 {
+            Some(x)
+        } else if x == 1 {
+            Some(y)
+        } else {
+            None
+        }
+    }).collect()
+}
+
+pub fn broadcast_shapes(a: &[usize], b: &[usize]) -> Result<Vec<usize>, TensorError> {
+    try_broadcast_shapes(a, b)
+        .ok_or_else(|| TensorError::new(format!("shapes {:?} and {:?} cannot be broadcast together", a, b)))
 }
 
 impl<T> Tensor<T> {
+    // Wraps an already logically-ordered buffer as a contiguous tensor
+    fn from_data(data: Vec<T>, shape: &[usize]) -> Tensor<T> {
+        let (size, strides) = get_size_and_strides(shape);
+        Tensor {
+            data: Rc::new(RefCell::new(data)),
+            base_index: 0,
+            size,
+            shape: shape.to_vec(),
+            strides
+        }
+    }
+
+    // Calls `f` with every index in logical order to fill a new tensor
+    pub fn from_fn<F: FnMut(&[usize]) -> T>(shape: &[usize], mut f: F) -> Tensor<T> {
+        let (size, _) = get_size_and_strides(shape);
+        let mut index = vec![0; shape.len()];
+        let mut data = Vec::with_capacity(size);
+        for _ in 0..size {
+            data.push(f(&index));
+            // Count up like an odometer, the last dimension turning fastest
+            for (i, &dim) in index.iter_mut().zip(shape).rev() {
+                *i += 1;
+                if *i < dim {
+                    break;
+                }
+                *i = 0;
+            }
+        }
+        Tensor::from_data(data, shape)
+    }
+
+    // Takes ownership of the buffer without copying it
+    pub fn from_raw_parts(data: Vec<T>, shape: Vec<usize>) -> Result<Tensor<T>, TensorError> {
+        let (size, _) = get_size_and_strides(&shape);
+        if data.len() != size {
+            return Err(TensorError::new(format!("buffer of length {} does not match shape {:?}", data.len(), shape)));
+        }
+        Ok(Tensor::from_data(data, &shape))
+    }
+
+    // Hands the buffer back without copying, which needs sole ownership of a buffer this tensor exactly covers
+    pub fn into_raw_parts(self) -> Result<(Vec<T>, Vec<usize>), TensorError> {
+        if !self.is_contiguous() || self.base_index != 0 || self.data.borrow().len() != self.size {
+            return Err(TensorError::new("only a contiguous tensor covering its whole buffer can give it up"));
+        }
+        let data = Rc::try_unwrap(self.data)
+            .map_err(|_| TensorError::new("cannot give up a buffer that is shared with other tensors"))?;
+        Ok((data.into_inner(), self.shape))
+    }
+
+    /* Swaps in a new backing buffer of the same length, dropping the old one.
+       Every tensor sharing the buffer sees the new values through its own layout */
+    pub fn replace_data(&self, new_data: Vec<T>) -> Result<(), TensorError> {
+        let mut data = self.data.borrow_mut();
+        if new_data.len() != data.len() {
+            return Err(TensorError::new(format!("replacement buffer has length {}, expected {}", new_data.len(), data.len())));
+        }
+        *data = new_data;
+        Ok(())
+    }
+
+    /* Any strided view over a buffer, e.g. overlapping windows with strides [1, 1]. The
+       layout is only checked to stay inside the buffer, so views may alias elements */
+    pub fn from_parts(data: Vec<T>, shape: Vec<usize>, strides: Vec<usize>, base_index: usize) -> Result<Tensor<T>, TensorError> {
+        if shape.len() != strides.len() {
+            return Err(TensorError::new(format!("shape {:?} and strides {:?} have different lengths", shape, strides)));
+        }
+        let (size, _) = get_size_and_strides(&shape);
+        // Even an empty layout slices the buffer at base_index, e.g. in PartialEq
+        if base_index > data.len() {
+            return Err(TensorError::new(format!("base index {} is past the end of a buffer of length {}", base_index, data.len())));
+        }
+        if size > 0 {
+            let last = base_index + shape.iter().zip(&strides).map(|(&n, &s)| (n - 1) * s).sum::<usize>();
+            if last >= data.len() {
+                return Err(TensorError::new(format!("layout reaches index {} of a buffer of length {}", last, data.len())));
+            }
+        }
+        Ok(Tensor {
+            data: Rc::new(RefCell::new(data)),
+            base_index,
+            size,
+            shape,
+            strides
+        })
+    }
+
     fn get_data_index(&self, index: &[usize], tile: bool) -> Result<usize, TensorError> {
         if index.len() > self.shape.len() {
             return Err(TensorError::new("index has too many dimensions"));
         }
         let mut data_index: usize = self.base_index;
-        for i in 0..index.len() {
-            let mut next_index = index[i];
+        for (i, &next_index) in index.iter().enumerate() {
+            let mut next_index = next_index;
             if tile {
                 next_index %= self.shape[i];
             } else if next_index >= self.shape[i] {
@@ -47,17 +242,64 @@ impl<T> Tensor<T> {
         let (new_size, _) = get_size_and_strides(&new_shape);
         Ok(Tensor {
             data: self.data.clone(),
-            base_index: base_index,
+            base_index,
             size: new_size,
             shape: new_shape,
             strides: self.strides[index.len()..].to_vec()
         })
     }
 
+    // get(&[i]), for the common case of indexing only the first dimension
+    pub fn at(&self, i: usize) -> Result<Tensor<T>, TensorError> {
+        self.get(&[i])
+    }
+
+    // A view of the given range of each leading dimension, later dimensions are kept whole
+    pub fn slice(&self, ranges: &[Range<usize>]) -> Result<Tensor<T>, TensorError> {
+        if ranges.len() > self.rank() {
+            return Err(TensorError::new("slice has too many dimensions"));
+        }
+        let mut result = self.clone();
+        for (d, range) in ranges.iter().enumerate() {
+            if range.start > range.end || range.end > self.shape[d] {
+                return Err(TensorError::new(format!("range {:?} is out of bounds for dimension {} of size {}", range, d, self.shape[d])));
+            }
+            result.base_index += range.start * self.strides[d];
+            result.shape[d] = range.len();
+        }
+        result.size = result.shape.iter().product();
+        Ok(result)
+    }
+
+    fn check_axis(&self, axis: usize) -> Result<(), TensorError> {
+        if axis >= self.rank() {
+            return Err(TensorError::new(format!("axis {} is out of range for a rank {} tensor", axis, self.rank())));
+        }
+        Ok(())
+    }
+
+    fn check_square(&self, op: &str) -> Result<usize, TensorError> {
+        if self.rank() != 2 || self.shape[0] != self.shape[1] {
+            return Err(TensorError::new(format!("{} requires a square matrix, got shape {:?}", op, self.shape)));
+        }
+        Ok(self.shape[0])
+    }
+
     pub fn rank(&self) -> usize {
         self.shape.len()
     }
 
+    pub fn dims(&self) -> usize {
+        self.rank()
+    }
+
+    // shape()[axis], but an error instead of a panic when the axis doesn't exist
+    pub fn dim(&self, axis: impl Into<Axis>) -> Result<usize, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        Ok(self.shape[axis])
+    }
+
     pub fn size(&self) -> usize {
         self.size
     }
@@ -66,39 +308,378 @@ impl<T> Tensor<T> {
         self.shape.as_slice()
     }
 
+    pub fn strides(&self) -> &[usize] {
+        self.strides.as_slice()
+    }
+
+    pub fn base_index(&self) -> usize {
+        self.base_index
+    }
+
+    // The element type's name (e.g. "f64") and size in bytes, for logging and memory reports
+    pub fn dtype_name(&self) -> &'static str {
+        std::any::type_name::<T>()
+    }
+
+    pub fn dtype_size(&self) -> usize {
+        std::mem::size_of::<T>()
+    }
+
+    /// Views the same buffer as elements of type `U`, e.g. the bit patterns of a `Tensor<f32>`
+    /// as a `Tensor<u32>`. Nothing is copied, so writes through either tensor show up in both.
+    ///
+    /// # Safety
+    ///
+    /// `T` and `U` must have the same size and alignment (checked, an error otherwise), and
+    /// bit validity has to hold in both directions for as long as either tensor is alive:
+    ///
+    /// - every `T` in this buffer must be a valid `U`, which rules out e.g. viewing `u8` as
+    ///   `bool` or `u32` as `char`;
+    /// - every `U` written through the returned view (`set_value`, `for_each_mut`, the
+    ///   in-place ops, ...) must be a valid `T`, because the original tensor reads the same
+    ///   memory. Viewing a `Tensor<bool>` as `u8` is fine for reading, but writing `2u8`
+    ///   puts an invalid `bool` in the original. The same goes for writes through `self`
+    ///   or any other view of the buffer, which the `Tensor<U>` will then read.
+    ///
+    /// Pairs like `f32`/`u32` or `i64`/`u64`, where every bit pattern is valid for both,
+    /// are always sound. Both types are `Copy`, so neither one's `Drop` runs on the
+    /// other's values when the last owner of the buffer goes away.
+    ///
+    /// The buffer has to stay shared with the other views, so `Rc<RefCell<Vec<T>>>` is cast
+    /// to `Rc<RefCell<Vec<U>>>` in place rather than rebuilt. That assumes `Vec<T>` and
+    /// `Vec<U>` have the same layout when `T` and `U` share size and alignment, which std
+    /// does not promise but which holds in practice. Requiring equal alignment is also
+    /// what lets the last owner free the allocation as a `Vec<U>`, since deallocation
+    /// reuses the alignment the buffer was allocated with.
+    pub unsafe fn reinterpret<U: Copy>(&self) -> Result<Tensor<U>, TensorError> where T: Copy {
+        if std::mem::size_of::<T>() != std::mem::size_of::<U>() || std::mem::align_of::<T>() != std::mem::align_of::<U>() {
+            return Err(TensorError::new(format!("cannot reinterpret {} as {}: size or alignment differs", std::any::type_name::<T>(), std::any::type_name::<U>())));
+        }
+        let data = Rc::from_raw(Rc::into_raw(self.data.clone()) as *const RefCell<Vec<U>>);
+        Ok(Tensor {
+            data,
+            base_index: self.base_index,
+            size: self.size,
+            shape: self.shape.clone(),
+            strides: self.strides.clone()
+        })
+    }
+
+    // Size of the whole backing buffer, which every view of it reports in full
+    pub fn memory_bytes(&self) -> usize {
+        self.data.borrow().len() * std::mem::size_of::<T>()
+    }
+
+    // What a contiguous copy of just this tensor's elements would take
+    pub fn logical_bytes(&self) -> usize {
+        self.size * std::mem::size_of::<T>()
+    }
+
+    pub fn layout(&self) -> Layout {
+        Layout {
+            shape: self.shape.clone(),
+            strides: self.strides.clone(),
+            base_index: self.base_index
+        }
+    }
+
+    // Lets shapes be destructured, e.g. `let [rows, cols] = t.shape_array()?;`
+    pub fn shape_array<const N: usize>(&self) -> Result<[usize; N], TensorError> {
+        self.shape.as_slice().try_into()
+            .map_err(|_| TensorError::new(format!("expected a rank {} tensor, got rank {}", N, self.rank())))
+    }
+
+    // Passes self through when the shape matches, for shape checks in the middle of a chain
+    pub fn expect_shape(&self, shape: &[usize]) -> Result<&Tensor<T>, TensorError> {
+        if self.shape != shape {
+            return Err(TensorError::new(format!("expected shape {:?}, got {:?}", shape, self.shape)));
+        }
+        Ok(self)
+    }
+
+    // Row-major conversions between a flat logical position and a coordinate in this shape
+    pub fn unravel_index(&self, flat: usize) -> Result<Vec<usize>, TensorError> {
+        if flat >= self.size {
+            return Err(TensorError::new(format!("flat index {} is out of range for {} elements", flat, self.size)));
+        }
+        let mut rest = flat;
+        let mut coords = vec![0; self.rank()];
+        for d in (0..self.rank()).rev() {
+            coords[d] = rest % self.shape[d];
+            rest /= self.shape[d];
+        }
+        Ok(coords)
+    }
+
+    pub fn ravel_index(&self, coords: &[usize]) -> Result<usize, TensorError> {
+        if coords.len() != self.rank() {
+            return Err(TensorError::new(format!("coordinate {:?} does not address a single element of a rank {} tensor", coords, self.rank())));
+        }
+        coords.iter().zip(&self.shape).enumerate().try_fold(0, |flat, (d, (&i, &n))| {
+            if i >= n {
+                return Err(TensorError::new(format!("index {} is out of range for dimension {}", i, d)));
+            }
+            Ok(flat * n + i)
+        })
+    }
+
     pub fn is_scalar(&self) -> bool {
         self.rank() == 0
     }
 
-    pub fn reshape(&self, new_shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+    // Stretches a size-1 dimension to `size` without copying by giving it a zero stride
+    pub fn broadcast_dim(&self, dim: impl Into<Axis>, size: usize) -> Result<Tensor<T>, TensorError> {
+        let dim = dim.into().0;
+        if dim >= self.rank() {
+            return Err(TensorError::new(format!("dimension {} is out of range for rank {}", dim, self.rank())));
+        }
+        if self.shape[dim] != 1 {
+            return Err(TensorError::new(format!("cannot broadcast dimension {} of size {}", dim, self.shape[dim])));
+        }
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        shape[dim] = size;
+        strides[dim] = 0;
+        Ok(Tensor {
+            data: self.data.clone(),
+            base_index: self.base_index,
+            size: self.size * size,
+            shape,
+            strides
+        })
+    }
+
+    // Checks for the binary ops ahead of time, without building an error on mismatch
+    pub fn can_broadcast_with<U>(&self, other: &Tensor<U>) -> bool {
+        self.broadcast_shape_with(other).is_some()
+    }
+
+    pub fn broadcast_shape_with<U>(&self, other: &Tensor<U>) -> Option<Vec<usize>> {
+        try_broadcast_shapes(&self.shape, &other.shape)
+    }
+
+    pub fn broadcast_to(&self, shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let error = || TensorError::new(format!("cannot broadcast shape {:?} to {:?}", self.shape, shape));
+        if shape.len() < self.rank() {
+            return Err(error());
+        }
+        // Missing leading dimensions are treated as size-1 dimensions
+        let extra = shape.len() - self.rank();
+        let mut result = Tensor {
+            data: self.data.clone(),
+            base_index: self.base_index,
+            size: self.size,
+            shape: [vec![1; extra], self.shape.clone()].concat(),
+            strides: [vec![0; extra], self.strides.clone()].concat()
+        };
+        for (d, &size) in shape.iter().enumerate() {
+            if result.shape[d] != size {
+                result = result.broadcast_dim(d, size).map_err(|_| error())?;
+            }
+        }
+        Ok(result)
+    }
+
+    // True when the view's elements sit in the buffer in logical order with no gaps
+    pub fn is_contiguous(&self) -> bool {
+        let (_, strides) = get_size_and_strides(&self.shape);
+        self.shape.iter().zip(&self.strides).zip(strides).all(|((&dim, &stride), expected)| dim == 1 || stride == expected)
+    }
+
+    // Reinterprets the buffer in place, so this can never copy
+    pub fn view(&self, new_shape: &[usize]) -> Result<Tensor<T>, TensorError> {
         let (size, strides) = get_size_and_strides(new_shape);
         if size != self.size {
             return Err(TensorError::new("new shape cannot be of a different size"))
         }
+        if !self.is_contiguous() {
+            return Err(TensorError::new("cannot view a non-contiguous tensor with a new shape"))
+        }
         Ok(Tensor {
             data: self.data.clone(),
             base_index: self.base_index,
             size: self.size,
             shape: new_shape.to_vec(),
-            strides: strides
+            strides
         })
     }
 
+    // Currently the same as view: non-contiguous tensors are an error, call contiguous first
+    pub fn reshape(&self, new_shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let result = self.view(new_shape)?;
+        trace_op!("reshape", [&self.shape], result.shape);
+        Ok(result)
+    }
+
+    // Array-taking forms of reshape, get and slice, so call sites can drop the `&`
+    pub fn reshape_arr<const N: usize>(&self, shape: [usize; N]) -> Result<Tensor<T>, TensorError> {
+        self.reshape(&shape)
+    }
+
+    pub fn get_arr<const N: usize>(&self, index: [usize; N]) -> Result<Tensor<T>, TensorError> {
+        self.get(&index)
+    }
+
+    pub fn slice_arr<const N: usize>(&self, ranges: [Range<usize>; N]) -> Result<Tensor<T>, TensorError> {
+        self.slice(&ranges)
+    }
+
+    pub fn transpose(&self, dim0: impl Into<Axis>, dim1: impl Into<Axis>) -> Result<Tensor<T>, TensorError> {
+        let dim0 = dim0.into().0;
+        let dim1 = dim1.into().0;
+        if dim0 >= self.rank() || dim1 >= self.rank() {
+            return Err(TensorError::new(format!("cannot transpose dimensions {} and {} of a rank {} tensor", dim0, dim1, self.rank())));
+        }
+        let mut result = self.clone();
+        result.shape.swap(dim0, dim1);
+        result.strides.swap(dim0, dim1);
+        Ok(result)
+    }
+
+    // A view with a new size-1 dimension inserted before `axis` (or appended when axis == rank)
+    pub fn unsqueeze(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        if axis > self.rank() {
+            return Err(TensorError::new(format!("cannot insert a dimension at axis {} of a rank {} tensor", axis, self.rank())));
+        }
+        let stride = if axis < self.rank() { self.shape[axis] * self.strides[axis] } else { 1 };
+        let mut result = self.clone();
+        result.shape.insert(axis, 1);
+        result.strides.insert(axis, stride);
+        Ok(result)
+    }
+
+    /* Views with leading size-1 dimensions added until the rank is at least 1, 2 or 3. Unlike
+       NumPy's atleast_3d, which gives [1, N, 1] and [M, N, 1], the 3-d case also only pads in front */
+    fn atleast(&self, rank: usize) -> Tensor<T> {
+        let mut result = self.clone();
+        while result.rank() < rank {
+            result = result.unsqueeze(0).unwrap();
+        }
+        result
+    }
+
+    pub fn atleast_1d(&self) -> Tensor<T> {
+        self.atleast(1)
+    }
+
+    pub fn atleast_2d(&self) -> Tensor<T> {
+        self.atleast(2)
+    }
+
+    pub fn atleast_3d(&self) -> Tensor<T> {
+        self.atleast(3)
+    }
+
+    // Swaps a square matrix's elements across the diagonal in its own buffer, so every view sees it
+    pub fn transpose_inplace(&self) -> Result<(), TensorError> {
+        let n = self.check_square("transpose_inplace")?;
+        if !self.is_contiguous() {
+            return Err(TensorError::new("transpose_inplace requires a contiguous tensor"));
+        }
+        let mut data = self.data.borrow_mut();
+        let at = |i: usize, j: usize| self.base_index + i * self.strides[0] + j * self.strides[1];
+        for i in 0..n {
+            for j in i + 1..n {
+                data.swap(at(i, j), at(j, i));
+            }
+        }
+        Ok(())
+    }
+
     pub fn flatten(&self) -> Result<Tensor<T>, TensorError> {
         self.reshape(&[self.size])
     }
+
+    pub fn permute(&self, dims: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let error = || TensorError::new(format!("{:?} is not a permutation of the dimensions of a rank {} tensor", dims, self.rank()));
+        if dims.len() != self.rank() {
+            return Err(error());
+        }
+        let mut seen = vec![false; self.rank()];
+        for &d in dims {
+            if d >= self.rank() || seen[d] {
+                return Err(error());
+            }
+            seen[d] = true;
+        }
+        let mut result = self.clone();
+        result.shape = dims.iter().map(|&d| self.shape[d]).collect();
+        result.strides = dims.iter().map(|&d| self.strides[d]).collect();
+        Ok(result)
+    }
+
+    // Sliding windows of `size` along `dim` every `step` elements, as a new trailing dimension
+    pub fn unfold(&self, dim: impl Into<Axis>, size: usize, step: usize) -> Result<Tensor<T>, TensorError> {
+        let dim = dim.into().0;
+        self.check_axis(dim)?;
+        if size == 0 || step == 0 {
+            return Err(TensorError::new("unfold needs a non-zero window size and step"));
+        }
+        if size > self.shape[dim] {
+            return Err(TensorError::new(format!("window of size {} does not fit in dimension {} of size {}", size, dim, self.shape[dim])));
+        }
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        shape[dim] = (self.shape[dim] - size) / step + 1;
+        strides[dim] *= step;
+        shape.push(size);
+        strides.push(self.strides[dim]);
+        let (size, _) = get_size_and_strides(&shape);
+        Ok(Tensor {
+            data: self.data.clone(),
+            base_index: self.base_index,
+            size,
+            shape,
+            strides
+        })
+    }
+
+    /* Visits every element in logical order without cloning it. The buffer stays
+       borrowed for the whole walk, so `f` must not mutate any view of it */
+    pub fn for_each_ref<F: FnMut(&T)>(&self, mut f: F) {
+        let data = self.data.borrow();
+        for i in TensorIndexIterator::new(self.clone()) {
+            f(&data[i]);
+        }
+    }
+
+    /* Visits every element in logical order with write access, holding one borrow_mut
+       for the walk. A broadcast view visits its shared elements once per position */
+    pub fn for_each_mut<F: FnMut(&mut T)>(&self, mut f: F) {
+        let mut data = self.data.borrow_mut();
+        for i in TensorIndexIterator::new(self.clone()) {
+            f(&mut data[i]);
+        }
+    }
+
+    // Views of each sub-tensor along the leading dimension
+    pub fn rows(&self) -> impl Iterator<Item=Tensor<T>> + '_ {
+        let n = self.shape.first().copied().unwrap_or(0);
+        (0..n).map(|i| self.get(&[i]).unwrap())
+    }
+
+    /* Applies f to each row and stacks the results. With no rows there is nothing to learn
+       the output row shape from, so the result is just an empty 1-d tensor */
+    pub fn map_rows<U: Clone, F: Fn(Tensor<T>) -> Tensor<U>>(&self, f: F) -> Result<Tensor<U>, TensorError> {
+        if self.shape.first() == Some(&0) {
+            return Ok(Tensor::from_iter(std::iter::empty()));
+        }
+        let results: Vec<Tensor<U>> = self.rows().map(f).collect();
+        Tensor::stack(&results, 0)
+    }
 }
 
 impl<A> FromIterator<A> for Tensor<A>  {
     fn from_iter<T: IntoIterator<Item=A>>(iter: T) -> Self {
         let v: Vec<A> = iter.into_iter().collect();
-        return Tensor {
+        Tensor {
             size: v.len(),
             shape: vec![v.len()],
             data: Rc::new(RefCell::new(v)),
             base_index: 0,
             strides: vec![1]
-        };
+        }
     }
 }
 
@@ -108,12 +689,22 @@ impl<T: Clone> Tensor<T> {
         Tensor {
             data: Rc::new(RefCell::new(vec![value; size])),
             base_index: 0,
-            size: size,
-            strides: strides,
+            size,
+            strides,
             shape: shape.to_vec()
         }
     }
 
+    /* A fresh contiguous tensor for a result you are about to overwrite. It is filled with
+       T::default(), but treat the contents as placeholders rather than as zeros */
+    pub fn empty(shape: &[usize]) -> Tensor<T> where T: Default {
+        Self::from_shape(T::default(), shape)
+    }
+
+    pub fn empty_like(&self) -> Tensor<T> where T: Default {
+        Self::empty(&self.shape)
+    }
+
     pub fn from_array(arr: &[T]) -> Tensor<T> {
         Tensor {
             base_index: 0,
@@ -124,70 +715,1807 @@ impl<T: Clone> Tensor<T> {
         }
     }
 
+    // Rectangular nested Vecs; a ragged row is an error naming where it is
+    pub fn from_vec2(v: &[Vec<T>]) -> Result<Tensor<T>, TensorError> {
+        let cols = v.first().map_or(0, |row| row.len());
+        if let Some(i) = v.iter().position(|row| row.len() != cols) {
+            return Err(TensorError::new(format!("row {} has length {}, expected {}", i, v[i].len(), cols)));
+        }
+        Ok(Tensor::from_data(v.concat(), &[v.len(), cols]))
+    }
+
+    pub fn from_vec3(v: &[Vec<Vec<T>>]) -> Result<Tensor<T>, TensorError> {
+        let rows = v.first().map_or(0, |plane| plane.len());
+        let cols = v.first().and_then(|plane| plane.first()).map_or(0, |row| row.len());
+        let mut data = Vec::with_capacity(v.len() * rows * cols);
+        for (i, plane) in v.iter().enumerate() {
+            if plane.len() != rows {
+                return Err(TensorError::new(format!("[{}] has {} rows, expected {}", i, plane.len(), rows)));
+            }
+            for (j, row) in plane.iter().enumerate() {
+                if row.len() != cols {
+                    return Err(TensorError::new(format!("row [{}][{}] has length {}, expected {}", i, j, row.len(), cols)));
+                }
+                data.extend_from_slice(row);
+            }
+        }
+        Ok(Tensor::from_data(data, &[v.len(), rows, cols]))
+    }
+
     pub fn scalar(value: T) -> Tensor<T> {
         Self::from_shape(value, &[])
     }
 
-    pub fn deep_clone(&self) -> Tensor<T> {
-        let mut new_data = Vec::<T>::with_capacity(self.size);
-        let new_shape =  self.shape.clone();
-        for v in self.clone() {
-            new_data.push(v);
+    // Uses NumPy's 'ij' indexing, so xs varies down the rows and ys across the columns
+    pub fn meshgrid(xs: &Tensor<T>, ys: &Tensor<T>) -> Result<(Tensor<T>, Tensor<T>), TensorError> {
+        if xs.rank() != 1 || ys.rank() != 1 {
+            return Err(TensorError::new("meshgrid requires rank-1 tensors"));
         }
-        let (size, strides) = get_size_and_strides(&new_shape);
-        Tensor {
-            data: Rc::new(RefCell::new(new_data)),
-            base_index: 0,
-            size: size,
-            shape: new_shape,
-            strides: strides
+        let shape = [xs.size, ys.size];
+        let xs_grid = xs.into_iter().flat_map(|x| std::iter::repeat_n(x, ys.size)).collect();
+        let ys_grid = (0..xs.size).flat_map(|_| ys).collect();
+        Ok((Tensor::from_data(xs_grid, &shape), Tensor::from_data(ys_grid, &shape)))
+    }
+
+    // Pads each dimension with (before, after) copies of `value`
+    pub fn pad(&self, padding: &[(usize, usize)], value: T) -> Result<Tensor<T>, TensorError> {
+        if padding.len() != self.rank() {
+            return Err(TensorError::new(format!("expected padding for {} dimensions, got {}", self.rank(), padding.len())));
         }
+        let shape: Vec<usize> = self.shape.iter().zip(padding).map(|(&d, &(before, after))| before + d + after).collect();
+        let result = Tensor::from_shape(value, &shape);
+        // A view of the region inside the padding, which self is copied into
+        let interior = Tensor {
+            data: result.data.clone(),
+            base_index: padding.iter().zip(&result.strides).map(|(&(before, _), &stride)| before * stride).sum(),
+            size: self.size,
+            shape: self.shape.clone(),
+            strides: result.strides.clone()
+        };
+        interior.assign(self);
+        Ok(result)
     }
-}
 
-impl<T> Tensor<T>
-where T: Clone + From<u32> + Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Div<Output=T>
-{
-    pub fn linspace(x0: T, xend: T, n: u32) -> Tensor<T> {
-        let n_as_t = T::from(n);
-        let dx = (xend - x0.clone()) / n_as_t;
-        Self::from_iter((0..n).map(|x| x0.clone() + T::from(x) * dx.clone()))
+    // Copies src element-wise into this view, the shapes must already match
+    fn assign(&self, src: &Tensor<T>) {
+        // Reading src while the buffer is mutably borrowed would panic if they share it
+        if Rc::ptr_eq(&self.data, &src.data) {
+            return self.assign(&src.deep_clone());
+        }
+        let mut data = self.data.borrow_mut();
+        for (i, v) in TensorIndexIterator::new(self.clone()).zip(src) {
+            data[i] = v;
+        }
     }
-}
 
-impl<T> Tensor<T> where Standard: Distribution<T> {
-    pub fn rand(shape: &[usize]) -> Tensor<T> {
-        let (size, strides) = get_size_and_strides(shape);
-        let mut rng = rand::thread_rng();
-        Tensor {
-            data: Rc::new(RefCell::new((0..size).map(|_| rng.gen()).collect())),
-            base_index: 0,
-            size: size,
-            strides: strides,
-            shape: shape.to_vec()
+    // get_value(&[i]) for rank-1 tensors
+    pub fn at_value(&self, i: usize) -> Result<T, TensorError> {
+        self.get_value(&[i])
+    }
+
+    pub fn set_value(&self, index: &[usize], value: T) -> Result<(), TensorError> {
+        if index.len() != self.rank() {
+            return Err(TensorError::new(format!("index {:?} does not address a single element of a rank {} tensor", index, self.rank())));
         }
+        let data_index = self.get_data_index(index, false)?;
+        self.data.borrow_mut()[data_index] = value;
+        Ok(())
     }
-}
 
-impl<T: Display> Display for Tensor<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let data = self.data.borrow();
+    // Writes src into the region of this tensor's buffer selected by `ranges`
+    pub fn set_slice(&self, ranges: &[Range<usize>], src: &Tensor<T>) -> Result<(), TensorError> {
+        let region = self.slice(ranges)?;
+        if region.shape != src.shape {
+            return Err(TensorError::new(format!("cannot write a tensor of shape {:?} into a region of shape {:?}", src.shape, region.shape)));
+        }
+        region.assign(src);
+        Ok(())
+    }
 
-        if self.is_scalar() {
-            write!(f, "{}", data[self.base_index])?;
-            return Ok(());
+    pub fn stack(tensors: &[Tensor<T>], axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        let first = tensors.first().ok_or_else(|| TensorError::new("cannot stack an empty list of tensors"))?;
+        if axis > first.rank() {
+            return Err(TensorError::new(format!("cannot stack rank {} tensors along axis {}", first.rank(), axis)));
         }
+        if let Some(t) = tensors.iter().find(|t| t.shape != first.shape) {
+            return Err(TensorError::new(format!("cannot stack tensors of shapes {:?} and {:?}", first.shape, t.shape)));
+        }
+        // Every tensor contributes a run of `inner` elements per index before `axis`
+        let outer: usize = first.shape[..axis].iter().product();
+        let inner: usize = first.shape[axis..].iter().product();
+        let parts: Vec<Vec<T>> = tensors.iter().map(|t| t.into_iter().collect()).collect();
+        let mut data = Vec::with_capacity(first.size * tensors.len());
+        for o in 0..outer {
+            for part in &parts {
+                data.extend_from_slice(&part[o * inner..(o + 1) * inner]);
+            }
+        }
+        let mut shape = first.shape.clone();
+        shape.insert(axis, tensors.len());
+        Ok(Tensor::from_data(data, &shape))
+    }
 
+    // A batch of equally shaped tensors along a new leading axis, same as stack(parts, 0)
+    pub fn from_tensors(parts: &[Tensor<T>]) -> Result<Tensor<T>, TensorError> {
+        Self::stack(parts, 0)
+    }
+
+    // from_tensors, but a mismatch names the index of the first offending part
+    pub fn try_from_tensors(parts: &[Tensor<T>]) -> Result<Tensor<T>, TensorError> {
+        if let Some(first) = parts.first() {
+            if let Some((i, t)) = parts.iter().enumerate().find(|(_, t)| t.shape != first.shape) {
+                return Err(TensorError::new(format!("tensor {} has shape {:?}, expected {:?} like tensor 0", i, t.shape, first.shape)));
+            }
+        }
+        Self::stack(parts, 0)
+    }
+
+    // Joins tensors along an existing axis; every other dimension has to match
+    pub fn concat(tensors: &[Tensor<T>], axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        let first = tensors.first().ok_or_else(|| TensorError::new("cannot concatenate an empty list of tensors"))?;
+        first.check_axis(axis)?;
+        let same_except_axis = |t: &Tensor<T>| {
+            t.rank() == first.rank() && (0..t.rank()).all(|d| d == axis || t.shape[d] == first.shape[d])
+        };
+        if let Some(t) = tensors.iter().find(|t| !same_except_axis(t)) {
+            return Err(TensorError::new(format!("cannot concatenate tensors of shapes {:?} and {:?} along axis {}", first.shape, t.shape, axis)));
+        }
+        let outer: usize = first.shape[..axis].iter().product();
+        let parts: Vec<Vec<T>> = tensors.iter().map(|t| t.into_iter().collect()).collect();
+        let mut data = Vec::with_capacity(tensors.iter().map(|t| t.size).sum());
+        for o in 0..outer {
+            for part in &parts {
+                let inner = part.len() / outer;
+                data.extend_from_slice(&part[o * inner..(o + 1) * inner]);
+            }
+        }
+        let mut shape = first.shape.clone();
+        shape[axis] = tensors.iter().map(|t| t.shape[axis]).sum();
+        Ok(Tensor::from_data(data, &shape))
+    }
+
+    // `times` copies of the whole tensor back to back along `axis`, e.g. [2, 3] x 3 on axis 0 is [6, 3]
+    pub fn repeat_along(&self, axis: impl Into<Axis>, times: usize) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        if times == 0 {
+            return Err(TensorError::new("repeat_along needs at least one copy"));
+        }
+        Self::concat(&vec![self.clone(); times], axis)
+    }
+
+    // Copy-on-write: gives this tensor a private buffer if any other tensor shares its current one
+    pub fn make_unique(&mut self) {
+        if Rc::strong_count(&self.data) > 1 {
+            *self = self.deep_clone();
+        }
+    }
+
+    pub fn contiguous(&self) -> Tensor<T> {
+        if self.is_contiguous() {
+            return self.clone();
+        }
+        self.deep_clone()
+    }
+
+    /* transpose().contiguous() for matrices, but copied tile by tile so that
+       large inputs are not read with a stride of a whole row each step */
+    pub fn transpose_contiguous(&self) -> Result<Tensor<T>, TensorError> {
+        const BLOCK: usize = 32;
+        if self.rank() != 2 {
+            return Err(TensorError::new(format!("transpose_contiguous requires a rank-2 tensor, got rank {}", self.rank())));
+        }
+        let (rows, cols) = (self.shape[0], self.shape[1]);
+        if self.size == 0 {
+            return Ok(Tensor::from_data(Vec::new(), &[cols, rows]));
+        }
+        let data = self.data.borrow();
+        let at = |i: usize, j: usize| self.base_index + i * self.strides[0] + j * self.strides[1];
+        let mut out = vec![data[self.base_index].clone(); self.size];
+        for i0 in (0..rows).step_by(BLOCK) {
+            for j0 in (0..cols).step_by(BLOCK) {
+                for i in i0..(i0 + BLOCK).min(rows) {
+                    for j in j0..(j0 + BLOCK).min(cols) {
+                        out[j * rows + i] = data[at(i, j)].clone();
+                    }
+                }
+            }
+        }
+        Ok(Tensor::from_data(out, &[cols, rows]))
+    }
+
+    /* einops-style axis shuffling, e.g. "h w -> w h" for a transpose, "(h w) c -> h w c"
+       to split an axis or "h w c -> (h w) c" to merge some. When a split can't infer
+       every size, name them in `sizes`, e.g. rearrange_with("(h w) -> h w", &[("h", 2)]) */
+    pub fn rearrange(&self, pattern: &str) -> Result<Tensor<T>, TensorError> {
+        self.rearrange_with(pattern, &[])
+    }
+
+    pub fn rearrange_with(&self, pattern: &str, sizes: &[(&str, usize)]) -> Result<Tensor<T>, TensorError> {
+        let (lhs, rhs) = pattern.split_once("->")
+            .ok_or_else(|| TensorError::new(format!("rearrange pattern {:?} has no \"->\"", pattern)))?;
+        let (lhs, rhs) = (parse_axis_groups(lhs)?, parse_axis_groups(rhs)?);
+        if lhs.len() != self.rank() {
+            return Err(TensorError::new(format!("pattern {:?} describes {} axes but the tensor has rank {}", pattern, lhs.len(), self.rank())));
+        }
+        let left: Vec<&str> = lhs.iter().flatten().copied().collect();
+        let mut right: Vec<&str> = rhs.iter().flatten().copied().collect();
+        for (i, name) in left.iter().enumerate() {
+            if left[..i].contains(name) {
+                return Err(TensorError::new(format!("axis {:?} appears more than once in {:?}", name, pattern)));
+            }
+        }
+        let mut sorted_left = left.clone();
+        sorted_left.sort();
+        right.sort();
+        if sorted_left != right {
+            return Err(TensorError::new(format!("both sides of {:?} must name the same axes, each once", pattern)));
+        }
+
+        // The size of every named axis, inferring at most one unknown per input group
+        let mut known: Vec<(&str, usize)> = Vec::new();
+        for (group, &dim) in lhs.iter().zip(&self.shape) {
+            let given = |name: &str| sizes.iter().find(|(n, _)| *n == name).map(|&(_, size)| size);
+            let unknown: Vec<&str> = group.iter().copied().filter(|&name| given(name).is_none()).collect();
+            let product: usize = group.iter().filter_map(|&name| given(name)).product();
+            let inferred = match unknown.len() {
+                0 => None,
+                1 if product > 0 && dim % product == 0 => Some(dim / product),
+                1 => return Err(TensorError::new(format!("cannot split a dimension of size {} into {:?}", dim, group))),
+                _ => return Err(TensorError::new(format!("cannot infer the sizes of {:?}, give all but one of them", unknown)))
+            };
+            if product * inferred.unwrap_or(1) != dim {
+                return Err(TensorError::new(format!("axes {:?} do not multiply to a dimension of size {}", group, dim)));
+            }
+            for &name in group {
+                known.push((name, given(name).or(inferred).unwrap()));
+            }
+        }
+        let size_of = |name: &str| known.iter().find(|(n, _)| *n == name).unwrap().1;
+
+        let mut result = self.clone();
+        if lhs.iter().any(|group| group.len() != 1) {
+            result = result.contiguous().reshape(&left.iter().map(|&name| size_of(name)).collect::<Vec<_>>())?;
+        }
+        let order: Vec<usize> = rhs.iter().flatten().map(|name| left.iter().position(|n| n == name).unwrap()).collect();
+        result = result.permute(&order)?;
+        if rhs.iter().any(|group| group.len() != 1) {
+            let shape: Vec<usize> = rhs.iter().map(|group| group.iter().map(|&name| size_of(name)).product()).collect();
+            result = result.contiguous().reshape(&shape)?;
+        }
+        Ok(result)
+    }
+
+    // A rank-1 tensor in logical order that always works, copying only when not contiguous
+    pub fn ravel(&self) -> Tensor<T> {
+        let mut result = self.contiguous();
+        result.shape = vec![self.size];
+        result.strides = vec![1];
+        result
+    }
+
+    pub fn deep_clone(&self) -> Tensor<T> {
+        let mut new_data = Vec::<T>::with_capacity(self.size);
+        let new_shape =  self.shape.clone();
+        for v in self.clone() {
+            new_data.push(v);
+        }
+        let (size, strides) = get_size_and_strides(&new_shape);
+        Tensor {
+            data: Rc::new(RefCell::new(new_data)),
+            base_index: 0,
+            size,
+            shape: new_shape,
+            strides
+        }
+    }
+
+    pub fn get_value(&self, index: &[usize]) -> Result<T, TensorError> {
+        if index.len() != self.rank() {
+            return Err(TensorError::new(format!("index {:?} does not address a single element of a rank {} tensor", index, self.rank())));
+        }
+        let data_index = self.get_data_index(index, false)?;
+        Ok(self.data.borrow()[data_index].clone())
+    }
+
+    // The elements in logical order as a fixed-size array, when there are exactly N of them
+    pub fn to_array<const N: usize>(&self) -> Result<[T; N], TensorError> {
+        let values: Vec<T> = self.into_iter().collect();
+        values.try_into().map_err(|v: Vec<T>| {
+            TensorError::new(format!("cannot convert a tensor of {} elements into an array of {}", v.len(), N))
+        })
+    }
+
+    pub fn map<U, F: FnMut(T) -> U>(&self, f: F) -> Tensor<U> {
+        Tensor::from_data(self.into_iter().map(f).collect(), &self.shape)
+    }
+
+    // Every element paired with its coordinate, in logical order
+    pub fn enumerate_indexed(&self) -> impl Iterator<Item=(Vec<usize>, T)> {
+        let mut indices = TensorIndexIterator::new(self.clone());
+        std::iter::from_fn(move || {
+            let coord = indices.index().to_vec();
+            let data_index = indices.next()?;
+            let value = indices.tensor.data.borrow()[data_index].clone();
+            Some((coord, value))
+        })
+    }
+
+    pub fn cast<U: From<T>>(&self) -> Tensor<U> {
+        self.map(U::from)
+    }
+
+    // The elements where the mask (broadcast to self's shape) is set, in logical order
+    pub fn masked_select(&self, mask: &Tensor<bool>) -> Result<Tensor<T>, TensorError> {
+        let mask = mask.broadcast_to(&self.shape)?;
+        Ok(self.into_iter().zip(mask).filter(|&(_, keep)| keep).map(|(x, _)| x).collect())
+    }
+
+    // Reductions over just the elements where the mask (broadcast to self's shape) is set
+    pub fn sum_masked(&self, mask: &Tensor<bool>) -> Result<T, TensorError> where T: Default + Add<Output=T> {
+        Ok(self.masked_select(mask)?.into_iter().fold(T::default(), |acc, x| acc + x))
+    }
+
+    pub fn mean_masked(&self, mask: &Tensor<bool>) -> Result<f64, TensorError> where T: Into<f64> {
+        let selected = self.masked_select(mask)?;
+        let count = selected.size;
+        if count == 0 {
+            return Err(TensorError::new("cannot take the mean when the mask selects no elements"));
+        }
+        Ok(selected.into_iter().map(Into::into).sum::<f64>() / count as f64)
+    }
+
+    // Self where the mask (broadcast to self's shape) is set, and `other` everywhere else
+    pub fn masked_select_or(&self, mask: &Tensor<bool>, other: T) -> Result<Tensor<T>, TensorError> {
+        let mask = mask.broadcast_to(&self.shape)?;
+        let data = self.into_iter().zip(mask).map(|(x, keep)| if keep { x } else { other.clone() }).collect();
+        Ok(Tensor::from_data(data, &self.shape))
+    }
+
+    pub fn select_scalar(cond: &Tensor<bool>, if_true: T, if_false: T) -> Tensor<T> {
+        cond.map(|c| if c { if_true.clone() } else { if_false.clone() })
+    }
+
+    // The elements at each of the given full coordinates, as a rank-1 tensor
+    pub fn gather_coords(&self, coords: &[&[usize]]) -> Result<Tensor<T>, TensorError> {
+        let data = self.data.borrow();
+        let mut values = Vec::with_capacity(coords.len());
+        for coord in coords {
+            if coord.len() != self.rank() {
+                return Err(TensorError::new(format!("coordinate {:?} does not address a single element of a rank {} tensor", coord, self.rank())));
+            }
+            values.push(data[self.get_data_index(coord, false)?].clone());
+        }
+        Ok(Tensor::from_data(values, &[coords.len()]))
+    }
+
+    // Windows over the last dimension shared by the 1-D pooling operations
+    fn pool_windows(&self, kernel: usize, stride: usize) -> Result<Tensor<T>, TensorError> {
+        if self.rank() != 1 && self.rank() != 2 {
+            return Err(TensorError::new("pooling requires a rank-1 or rank-2 tensor"));
+        }
+        self.unfold(self.rank() - 1, kernel, stride)
+    }
+
+    pub fn max_pool1d(&self, kernel: usize, stride: usize) -> Result<Tensor<T>, TensorError> where T: PartialOrd {
+        let windows = self.pool_windows(kernel, stride)?;
+        windows.apply_along_axis(windows.rank() - 1, |w| {
+            w[1..].iter().fold(w[0].clone(), |max, x| if *x > max { x.clone() } else { max })
+        })
+    }
+
+    pub fn avg_pool1d(&self, kernel: usize, stride: usize) -> Result<Tensor<T>, TensorError>
+    where T: Numeric
+    {
+        let windows = self.pool_windows(kernel, stride)?;
+        windows.apply_along_axis(windows.rank() - 1, |w| {
+            w[1..].iter().fold(w[0].clone(), |sum, x| sum + x.clone()) / T::from_usize(kernel)
+        })
+    }
+
+    /* Replaces every 1-D slice along `axis` with the `len` values `f` returns for it.
+       Slices are handed over with the axis moved to the end, where they're contiguous */
+    fn map_slices<U: Clone, F: FnMut(&[T]) -> Vec<U>>(&self, axis: usize, len: usize, mut f: F) -> Result<Tensor<U>, TensorError> {
+        self.check_axis(axis)?;
+        let mut dims: Vec<usize> = (0..self.rank()).filter(|&d| d != axis).collect();
+        dims.push(axis);
+        let values: Vec<T> = self.permute(&dims)?.into_iter().collect();
+        let n = self.shape[axis];
+        let count: usize = dims[..dims.len() - 1].iter().map(|&d| self.shape[d]).product();
+        let mut result = Vec::with_capacity(count * len);
+        for i in 0..count {
+            result.extend(f(&values[i * n..(i + 1) * n]));
+        }
+        let mut shape: Vec<usize> = dims.iter().map(|&d| self.shape[d]).collect();
+        shape[dims.len() - 1] = len;
+        let mut inverse = vec![0; dims.len()];
+        for (i, &d) in dims.iter().enumerate() {
+            inverse[d] = i;
+        }
+        Ok(Tensor::from_data(result, &shape).permute(&inverse)?.contiguous())
+    }
+
+    // Running extreme along `axis`, where replace(new, current) says when the new value takes over
+    fn cumulative_extreme<F: Fn(&T, &T) -> bool>(&self, axis: usize, replace: F) -> Result<Tensor<T>, TensorError> {
+        self.check_axis(axis)?;
+        self.map_slices(axis, self.shape[axis], |slice| {
+            let mut result: Vec<T> = Vec::with_capacity(slice.len());
+            for x in slice {
+                match result.last() {
+                    Some(current) if !replace(x, current) => result.push(current.clone()),
+                    _ => result.push(x.clone())
+                }
+            }
+            result
+        })
+    }
+
+    pub fn cummax(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> where T: PartialOrd {
+        let axis = axis.into().0;
+        self.cumulative_extreme(axis, |x, current| x > current)
+    }
+
+    pub fn cummin(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> where T: PartialOrd {
+        let axis = axis.into().0;
+        self.cumulative_extreme(axis, |x, current| x < current)
+    }
+
+    // A copy with the order reversed along `axis`; strides are unsigned, so this can't be a view
+    pub fn flip(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        self.map_slices(axis, self.shape[axis], |slice| slice.iter().rev().cloned().collect())
+    }
+
+    // Rotates a matrix by k quarter turns counterclockwise (clockwise for negative k), like NumPy
+    pub fn rot90(&self, k: i32) -> Result<Tensor<T>, TensorError> {
+        if self.rank() != 2 {
+            return Err(TensorError::new(format!("rot90 requires a rank-2 tensor, got rank {}", self.rank())));
+        }
+        match k.rem_euclid(4) {
+            0 => Ok(self.clone()),
+            1 => self.flip(1)?.transpose(0, 1),
+            2 => self.flip(0)?.flip(1),
+            _ => self.transpose(0, 1)?.flip(1)
+        }
+    }
+
+    // Consecutive differences along `axis`, one shorter than the input there, like NumPy's diff
+    pub fn diff(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError> where T: Sub<Output=T> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        let n = self.shape[axis];
+        if n < 2 {
+            return Err(TensorError::new(format!("diff needs at least 2 elements along axis {}, got {}", axis, n)));
+        }
+        self.map_slices(axis, n - 1, |slice| slice.windows(2).map(|w| w[1].clone() - w[0].clone()).collect())
+    }
+
+    // The extreme value along `axis` and where it was, found in one pass; ties keep the first
+    fn extreme_with_index<F: Fn(&T, &T) -> bool>(&self, axis: usize, replace: F) -> Result<(Tensor<T>, Tensor<usize>), TensorError> {
+        self.check_axis(axis)?;
+        if self.shape[axis] == 0 {
+            return Err(TensorError::new(format!("cannot reduce along empty axis {}", axis)));
+        }
+        let pairs = self.apply_along_axis(axis, |slice| {
+            let mut best = 0;
+            for (i, x) in slice.iter().enumerate().skip(1) {
+                if replace(x, &slice[best]) {
+                    best = i;
+                }
+            }
+            (slice[best].clone(), best)
+        })?;
+        Ok((pairs.map(|(v, _)| v), pairs.map(|(_, i)| i)))
+    }
+
+    pub fn max_with_index_axis(&self, axis: impl Into<Axis>) -> Result<(Tensor<T>, Tensor<usize>), TensorError> where T: PartialOrd {
+        self.extreme_with_index(axis.into().0, |x, best| x > best)
+    }
+
+    pub fn min_with_index_axis(&self, axis: impl Into<Axis>) -> Result<(Tensor<T>, Tensor<usize>), TensorError> where T: PartialOrd {
+        self.extreme_with_index(axis.into().0, |x, best| x < best)
+    }
+
+    // The k largest (or smallest) values along `axis` in order, with their indices
+    pub fn topk(&self, k: usize, axis: impl Into<Axis>, largest: bool) -> Result<(Tensor<T>, Tensor<usize>), TensorError> where T: PartialOrd {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        if k > self.shape[axis] {
+            return Err(TensorError::new(format!("k = {} is larger than dimension {} of size {}", k, axis, self.shape[axis])));
+        }
+        let pairs = self.map_slices(axis, k, |slice| {
+            let mut order: Vec<usize> = (0..slice.len()).collect();
+            // The sort is stable, so ties keep their original order
+            order.sort_by(|&a, &b| {
+                let ordering = slice[a].partial_cmp(&slice[b]).unwrap_or(Ordering::Equal);
+                if largest { ordering.reverse() } else { ordering }
+            });
+            order.into_iter().take(k).map(|i| (slice[i].clone(), i)).collect()
+        })?;
+        Ok((pairs.map(|(v, _)| v), pairs.map(|(_, i)| i)))
+    }
+
+    // Calls `f` on every 1-D slice along `axis`, so the result has that axis removed
+    pub fn apply_along_axis<U, F: Fn(&[T]) -> U>(&self, axis: impl Into<Axis>, f: F) -> Result<Tensor<U>, TensorError> {
+        let axis = axis.into().0;
+        self.reduce_axes(&[axis], false, f)
+    }
+
+    /* Collapses all of `axes` at once, calling `f` with the elements of each reduced
+       block. Moving those axes to the end makes every block a contiguous run */
+    fn reduce_axes<U, F: Fn(&[T]) -> U>(&self, axes: &[usize], keepdim: bool, f: F) -> Result<Tensor<U>, TensorError> {
+        for (i, &axis) in axes.iter().enumerate() {
+            self.check_axis(axis)?;
+            if axes[..i].contains(&axis) {
+                return Err(TensorError::new(format!("axis {} is reduced more than once", axis)));
+            }
+        }
+        let kept: Vec<usize> = (0..self.rank()).filter(|d| !axes.contains(d)).collect();
+        let values: Vec<T> = self.permute(&[kept.clone(), axes.to_vec()].concat())?.into_iter().collect();
+        let len: usize = axes.iter().map(|&d| self.shape[d]).product();
+        let count: usize = kept.iter().map(|&d| self.shape[d]).product();
+        let shape: Vec<usize> = if keepdim {
+            self.shape.iter().enumerate().map(|(d, &n)| if axes.contains(&d) { 1 } else { n }).collect()
+        } else {
+            kept.iter().map(|&d| self.shape[d]).collect()
+        };
+        trace_op!("reduce", [&self.shape], shape);
+        Ok(Tensor::from_data((0..count).map(|i| f(&values[i * len..(i + 1) * len])).collect(), &shape))
+    }
+
+    pub fn sum_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor<T>, TensorError>
+    where T: Default + for<'a> Add<&'a T, Output=T>
+    {
+        self.reduce_axes(axes, keepdim, |block| block.iter().fold(T::default(), |acc, x| acc + x))
+    }
+
+    pub fn sum_axis(&self, axis: impl Into<Axis>) -> Result<Tensor<T>, TensorError>
+    where T: Default + for<'a> Add<&'a T, Output=T>
+    {
+        let axis = axis.into().0;
+        self.sum_axes(&[axis], false)
+    }
+
+    pub fn mean_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor<T>, TensorError>
+    where T: Numeric + for<'a> Add<&'a T, Output=T>
+    {
+        if axes.iter().any(|&d| self.shape.get(d) == Some(&0)) {
+            return Err(TensorError::new("cannot take the mean over an empty dimension"));
+        }
+        self.reduce_axes(axes, keepdim, |block| {
+            block.iter().fold(T::default(), |acc, x| acc + x) / T::from_usize(block.len())
+        })
+    }
+
+    pub fn max_axes(&self, axes: &[usize], keepdim: bool) -> Result<Tensor<T>, TensorError> where T: PartialOrd {
+        if axes.iter().any(|&d| self.shape.get(d) == Some(&0)) {
+            return Err(TensorError::new("cannot take the max over an empty dimension"));
+        }
+        self.reduce_axes(axes, keepdim, |block| {
+            block[1..].iter().fold(block[0].clone(), |max, x| if *x > max { x.clone() } else { max })
+        })
+    }
+
+    // Applies `f` element-wise after broadcasting both tensors to a common shape
+    fn zip_with<U, F: FnMut(T, T) -> U>(&self, other: &Tensor<T>, mut f: F) -> Result<Tensor<U>, TensorError> {
+        let shape = broadcast_shapes(&self.shape, &other.shape)?;
+        let lhs = self.broadcast_to(&shape)?;
+        let rhs = other.broadcast_to(&shape)?;
+        Ok(Tensor::from_data(lhs.into_iter().zip(rhs).map(|(x, y)| f(x, y)).collect(), &shape))
+    }
+
+    // Element-wise == after broadcasting, unlike PartialEq which compares whole tensors
+    pub fn eq_broadcast(&self, other: &Tensor<T>) -> Result<Tensor<bool>, TensorError> where T: PartialEq {
+        self.zip_with(other, |x, y| x == y)
+    }
+
+    pub fn add(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Add<Output=T> {
+        self.zip_with(other, |x, y| x + y)
+    }
+
+    pub fn sub(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Sub<Output=T> {
+        self.zip_with(other, |x, y| x - y)
+    }
+
+    pub fn mul(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Mul<Output=T> {
+        self.zip_with(other, |x, y| x * y)
+    }
+
+    pub fn div(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Div<Output=T> {
+        self.zip_with(other, |x, y| x / y)
+    }
+
+    // Writes f(self, other) element-wise into out's existing buffer
+    fn zip_into<F: FnMut(T, T) -> T>(&self, other: &Tensor<T>, out: &mut Tensor<T>, mut f: F) -> Result<(), TensorError> {
+        if self.shape != other.shape || self.shape != out.shape {
+            return Err(TensorError::new(format!("shapes {:?}, {:?} and {:?} must all match", self.shape, other.shape, out.shape)));
+        }
+        let out_indices = TensorIndexIterator::new(out.clone());
+        // Reading an input while out's buffer is mutably borrowed would panic if they're the same buffer
+        if Rc::ptr_eq(&out.data, &self.data) || Rc::ptr_eq(&out.data, &other.data) {
+            let values: Vec<T> = self.into_iter().zip(other).map(|(x, y)| f(x, y)).collect();
+            let mut data = out.data.borrow_mut();
+            for (i, v) in out_indices.zip(values) {
+                data[i] = v;
+            }
+        } else {
+            let mut data = out.data.borrow_mut();
+            for (i, (x, y)) in out_indices.zip(self.into_iter().zip(other)) {
+                data[i] = f(x, y);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_into(&self, other: &Tensor<T>, out: &mut Tensor<T>) -> Result<(), TensorError> where T: Add<Output=T> {
+        self.zip_into(other, out, |x, y| x + y)
+    }
+
+    pub fn sub_into(&self, other: &Tensor<T>, out: &mut Tensor<T>) -> Result<(), TensorError> where T: Sub<Output=T> {
+        self.zip_into(other, out, |x, y| x - y)
+    }
+
+    pub fn mul_into(&self, other: &Tensor<T>, out: &mut Tensor<T>) -> Result<(), TensorError> where T: Mul<Output=T> {
+        self.zip_into(other, out, |x, y| x * y)
+    }
+
+    pub fn div_into(&self, other: &Tensor<T>, out: &mut Tensor<T>) -> Result<(), TensorError> where T: Div<Output=T> {
+        self.zip_into(other, out, |x, y| x / y)
+    }
+
+    pub fn add_scalar(&self, s: T) -> Tensor<T> where T: Add<Output=T> {
+        self.map(|x| x + s.clone())
+    }
+
+    pub fn sub_scalar(&self, s: T) -> Tensor<T> where T: Sub<Output=T> {
+        self.map(|x| x - s.clone())
+    }
+
+    pub fn mul_scalar(&self, s: T) -> Tensor<T> where T: Mul<Output=T> {
+        self.map(|x| x * s.clone())
+    }
+
+    pub fn div_scalar(&self, s: T) -> Tensor<T> where T: Div<Output=T> {
+        self.map(|x| x / s.clone())
+    }
+
+    /* In-place scalar ops that return self for chaining, e.g. t.mul_scalar_(2).add_scalar_(1).
+       They write to the shared buffer, so every view of it sees the change. Each buffer slot
+       is updated once, even when the layout reaches it from several positions (broadcasts,
+       overlapping windows from unfold or from_parts) */
+    fn update_scalar_<F: Fn(T) -> T>(&self, f: F) -> &Self {
+        let mut indices: Vec<usize> = TensorIndexIterator::new(self.clone()).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        let mut data = self.data.borrow_mut();
+        for i in indices {
+            data[i] = f(data[i].clone());
+        }
+        self
+    }
+
+    pub fn add_scalar_(&self, s: T) -> &Self where T: Add<Output=T> {
+        self.update_scalar_(|x| x + s.clone())
+    }
+
+    pub fn sub_scalar_(&self, s: T) -> &Self where T: Sub<Output=T> {
+        self.update_scalar_(|x| x - s.clone())
+    }
+
+    pub fn mul_scalar_(&self, s: T) -> &Self where T: Mul<Output=T> {
+        self.update_scalar_(|x| x * s.clone())
+    }
+
+    pub fn div_scalar_(&self, s: T) -> &Self where T: Div<Output=T> {
+        self.update_scalar_(|x| x / s.clone())
+    }
+
+    // self + x·yᵀ in one pass, without building the outer product first
+    pub fn add_outer(&self, x: &Tensor<T>, y: &Tensor<T>) -> Result<Tensor<T>, TensorError>
+    where T: Add<Output=T> + Mul<Output=T>
+    {
+        if self.rank() != 2 || x.rank() != 1 || y.rank() != 1 || x.shape[0] != self.shape[0] || y.shape[0] != self.shape[1] {
+            return Err(TensorError::new(format!("cannot add the outer product of shapes {:?} and {:?} to shape {:?}", x.shape, y.shape, self.shape)));
+        }
+        let xs: Vec<T> = x.into_iter().collect();
+        let ys: Vec<T> = y.into_iter().collect();
+        let m = ys.len();
+        let data = self.into_iter().enumerate().map(|(i, v)| v + xs[i / m].clone() * ys[i % m].clone()).collect();
+        Ok(Tensor::from_data(data, &self.shape))
+    }
+
+    pub fn bitand(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: BitAnd<Output=T> {
+        self.zip_with(other, |x, y| x & y)
+    }
+
+    pub fn bitor(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: BitOr<Output=T> {
+        self.zip_with(other, |x, y| x | y)
+    }
+
+    pub fn bitxor(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: BitXor<Output=T> {
+        self.zip_with(other, |x, y| x ^ y)
+    }
+
+    pub fn shl(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Shl<Output=T> {
+        self.zip_with(other, |x, y| x << y)
+    }
+
+    pub fn shr(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Shr<Output=T> {
+        self.zip_with(other, |x, y| x >> y)
+    }
+
+    pub fn bitand_scalar(&self, s: T) -> Tensor<T> where T: BitAnd<Output=T> {
+        self.map(|x| x & s.clone())
+    }
+
+    pub fn bitor_scalar(&self, s: T) -> Tensor<T> where T: BitOr<Output=T> {
+        self.map(|x| x | s.clone())
+    }
+
+    pub fn bitxor_scalar(&self, s: T) -> Tensor<T> where T: BitXor<Output=T> {
+        self.map(|x| x ^ s.clone())
+    }
+
+    pub fn shl_scalar(&self, s: T) -> Tensor<T> where T: Shl<Output=T> {
+        self.map(|x| x << s.clone())
+    }
+
+    pub fn shr_scalar(&self, s: T) -> Tensor<T> where T: Shr<Output=T> {
+        self.map(|x| x >> s.clone())
+    }
+}
+
+// Integer division, Default stands in for zero when checking for division by zero
+impl<T: Clone + Default + PartialEq> Tensor<T> {
+    fn check_divisors(divisors: &Tensor<T>) -> Result<(), TensorError> {
+        if divisors.into_iter().any(|d| d == T::default()) {
+            return Err(TensorError::new("division by zero"));
+        }
+        Ok(())
+    }
+
+    pub fn rem(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> where T: Rem<Output=T> {
+        Self::check_divisors(other)?;
+        self.zip_with(other, |x, y| x % y)
+    }
+
+    pub fn rem_scalar(&self, s: T) -> Result<Tensor<T>, TensorError> where T: Rem<Output=T> {
+        self.rem(&Tensor::scalar(s))
+    }
+
+    /// Rounds towards negative infinity like Python's //, unlike the truncating div.
+    ///
+    /// # Panics
+    ///
+    /// For signed integers, dividing the minimum value by -1 (e.g. `i32::MIN` by `-1`)
+    /// overflows. Integer `/` panics on that in every build profile, and so does this.
+    pub fn floor_div(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError>
+    where T: Numeric + Rem<Output=T>
+    {
+        Self::check_divisors(other)?;
+        self.zip_with(other, |x, y| {
+            let zero = T::default();
+            let q = x.clone() / y.clone();
+            let r = x % y.clone();
+            if r != zero && ((r < zero) != (y < zero)) { q - T::from(true) } else { q }
+        })
+    }
+
+    pub fn floor_div_scalar(&self, s: T) -> Result<Tensor<T>, TensorError>
+    where T: Numeric + Rem<Output=T>
+    {
+        self.floor_div(&Tensor::scalar(s))
+    }
+}
+
+// Default stands in for zero so that types without From<bool> (e.g. complex numbers) work
+// Reductions accumulate by reference, so expensive-to-clone element types are never cloned
+impl<T: Default> Tensor<T> {
+    pub fn sum(&self) -> T where T: for<'a> Add<&'a T, Output=T> {
+        let mut acc = T::default();
+        self.for_each_ref(|x| acc = std::mem::take(&mut acc) + x);
+        trace_op!("sum", [&self.shape], Vec::<usize>::new());
+        acc
+    }
+
+    pub fn product(&self) -> T where T: From<bool> + for<'a> Mul<&'a T, Output=T> {
+        let mut acc = T::from(true);
+        self.for_each_ref(|x| acc = std::mem::take(&mut acc) * x);
+        trace_op!("product", [&self.shape], Vec::<usize>::new());
+        acc
+    }
+}
+
+impl<T: Clone + Default + Add<Output=T> + Mul<Output=T>> Tensor<T> {
+    /* Matrix product over the last two dimensions. Any leading dimensions are batch
+       dimensions and broadcast like NumPy's @, so [5, 2, 3] times [3, 4] is [5, 2, 4] */
+    pub fn matmul(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        if self.rank() < 2 || other.rank() < 2 {
+            return Err(TensorError::new("matmul requires tensors of rank 2 or more"));
+        }
+        let (r, s) = (self.rank(), other.rank());
+        let (n, k, m) = (self.shape[r - 2], self.shape[r - 1], other.shape[s - 1]);
+        if other.shape[s - 2] != k {
+            return Err(TensorError::new(format!("cannot multiply matrices of shapes {:?} and {:?}", self.shape, other.shape)));
+        }
+        let batch = broadcast_shapes(&self.shape[..r - 2], &other.shape[..s - 2])?;
+        let lhs: Vec<T> = self.broadcast_to(&[batch.as_slice(), &[n, k]].concat())?.into_iter().collect();
+        let rhs: Vec<T> = other.broadcast_to(&[batch.as_slice(), &[k, m]].concat())?.into_iter().collect();
+        let count: usize = batch.iter().product();
+        let mut result = Vec::with_capacity(count * n * m);
+        for b in 0..count {
+            let (lhs, rhs) = (&lhs[b * n * k..(b + 1) * n * k], &rhs[b * k * m..(b + 1) * k * m]);
+            for i in 0..n {
+                for j in 0..m {
+                    let mut acc = T::default();
+                    for p in 0..k {
+                        acc = acc + lhs[i * k + p].clone() * rhs[p * m + j].clone();
+                    }
+                    result.push(acc);
+                }
+            }
+        }
+        let shape = [batch.as_slice(), &[n, m]].concat();
+        trace_op!("matmul", [&self.shape, &other.shape], shape);
+        Ok(Tensor::from_data(result, &shape))
+    }
+
+    // Strict batched matmul: both [b, n, k] and [b, k, m] with the same b, no broadcasting
+    pub fn bmm(&self, other: &Tensor<T>) -> Result<Tensor<T>, TensorError> {
+        if self.rank() != 3 || other.rank() != 3 || self.shape[0] != other.shape[0] {
+            return Err(TensorError::new(format!("bmm requires rank-3 tensors with the same batch size, got {:?} and {:?}", self.shape, other.shape)));
+        }
+        self.matmul(other)
+    }
+
+    pub fn dot(&self, other: &Tensor<T>) -> Result<T, TensorError> {
+        if self.rank() != 1 || other.rank() != 1 || self.size != other.size {
+            return Err(TensorError::new(format!("cannot dot tensors of shapes {:?} and {:?}", self.shape, other.shape)));
+        }
+        Ok(self.into_iter().zip(other).fold(T::default(), |acc, (x, y)| acc + x * y))
+    }
+
+    // dot for any rank: the sum of element-wise products of two same-shaped tensors
+    pub fn frobenius_inner(&self, other: &Tensor<T>) -> Result<T, TensorError> {
+        if self.shape != other.shape {
+            return Err(TensorError::new(format!("cannot take the inner product of shapes {:?} and {:?}", self.shape, other.shape)));
+        }
+        Ok(self.into_iter().zip(other).fold(T::default(), |acc, (x, y)| acc + x * y))
+    }
+
+    // Cross-correlation (no kernel flip) of a rank-1 input, zero padded on both sides
+    pub fn conv1d(&self, kernel: &Tensor<T>, stride: usize, padding: usize) -> Result<Tensor<T>, TensorError> {
+        if self.rank() != 1 || kernel.rank() != 1 {
+            return Err(TensorError::new("conv1d requires a rank-1 input and kernel"));
+        }
+        let padded = self.pad(&[(padding, padding)], T::default())?;
+        if kernel.size > padded.size {
+            return Err(TensorError::new(format!("kernel of size {} does not fit an input of size {} with padding {}", kernel.size, self.size, padding)));
+        }
+        let windows = padded.unfold(0, kernel.size, stride)?;
+        let values = windows.rows().map(|w| w.dot(kernel)).collect::<Result<Vec<T>, TensorError>>()?;
+        Ok(Tensor::from_iter(values))
+    }
+
+    // Contracts the paired axes, leaving the free axes of self followed by those of other
+    pub fn tensordot(&self, other: &Tensor<T>, axes: (&[usize], &[usize])) -> Result<Tensor<T>, TensorError> {
+        let (lhs_axes, rhs_axes) = axes;
+        if lhs_axes.len() != rhs_axes.len() {
+            return Err(TensorError::new("tensordot needs the same number of axes for both tensors"));
+        }
+        for (&a, &b) in lhs_axes.iter().zip(rhs_axes) {
+            if a >= self.rank() || b >= other.rank() {
+                return Err(TensorError::new(format!("tensordot axes {} and {} are out of range", a, b)));
+            }
+            if self.shape[a] != other.shape[b] {
+                return Err(TensorError::new(format!("cannot contract axis {} of size {} with axis {} of size {}", a, self.shape[a], b, other.shape[b])));
+            }
+        }
+        // With the contracted axes moved to the end of self and the front of other it is a matmul
+        let lhs_free: Vec<usize> = (0..self.rank()).filter(|d| !lhs_axes.contains(d)).collect();
+        let rhs_free: Vec<usize> = (0..other.rank()).filter(|d| !rhs_axes.contains(d)).collect();
+        let lhs = self.permute(&[lhs_free.clone(), lhs_axes.to_vec()].concat())?.contiguous();
+        let rhs = other.permute(&[rhs_axes.to_vec(), rhs_free.clone()].concat())?.contiguous();
+        let n: usize = lhs_free.iter().map(|&d| self.shape[d]).product();
+        let k: usize = lhs_axes.iter().map(|&d| self.shape[d]).product();
+        let m: usize = rhs_free.iter().map(|&d| other.shape[d]).product();
+        let shape: Vec<usize> = lhs_free.iter().map(|&d| self.shape[d]).chain(rhs_free.iter().map(|&d| other.shape[d])).collect();
+        lhs.reshape(&[n, k])?.matmul(&rhs.reshape(&[k, m])?)?.reshape(&shape)
+    }
+}
+
+// Generic over PartialOrd so that it also covers half precision floats
+impl<T: Clone + PartialOrd + Default> Tensor<T> {
+    pub fn relu(&self) -> Tensor<T> {
+        self.map(|x| if x > T::default() { x } else { T::default() })
+    }
+}
+
+// From<bool> rather than a number type, as it's the one conversion every primitive has
+impl<T: Clone + From<bool>> Tensor<T> {
+    pub fn zeros(shape: &[usize]) -> Tensor<T> {
+        Self::from_shape(T::from(false), shape)
+    }
+
+    pub fn ones(shape: &[usize]) -> Tensor<T> {
+        Self::from_shape(T::from(true), shape)
+    }
+
+    // Dense from (coordinate, value) pairs, zero elsewhere; a repeated coordinate keeps its last value
+    pub fn from_coo(coords: &[(Vec<usize>, T)], shape: &[usize]) -> Result<Tensor<T>, TensorError> {
+        let result = Self::zeros(shape);
+        for (coord, value) in coords {
+            result.set_value(coord, value.clone())?;
+        }
+        Ok(result)
+    }
+
+    pub fn eye(n: usize) -> Tensor<T> {
+        Tensor::from_data((0..n * n).map(|i| T::from(i % (n + 1) == 0)).collect(), &[n, n])
+    }
+
+    // Zeroes every element of a matrix whose (row, column) isn't kept
+    fn mask_triangle<F: Fn(isize, isize) -> bool>(&self, keep: F) -> Result<Tensor<T>, TensorError> {
+        if self.rank() != 2 {
+            return Err(TensorError::new("triu and tril require a rank-2 tensor"));
+        }
+        let cols = self.shape[1];
+        let data = self.into_iter().enumerate()
+            .map(|(n, x)| if keep((n / cols) as isize, (n % cols) as isize) { x } else { T::from(false) })
+            .collect();
+        Ok(Tensor::from_data(data, &self.shape))
+    }
+
+    // Keeps the elements on and above the given diagonal, positive offsets are above the main one
+    pub fn triu(&self, diagonal: isize) -> Result<Tensor<T>, TensorError> {
+        self.mask_triangle(|i, j| j - i >= diagonal)
+    }
+
+    // Keeps the elements on and below the given diagonal
+    pub fn tril(&self, diagonal: isize) -> Result<Tensor<T>, TensorError> {
+        self.mask_triangle(|i, j| j - i <= diagonal)
+    }
+}
+
+impl<T: Numeric> Tensor<T> {
+    pub fn linspace(x0: T, xend: T, n: u32) -> Tensor<T> {
+        let dx = (xend - x0.clone()) / T::from_usize(n as usize);
+        Self::from_iter((0..n as usize).map(|x| x0.clone() + T::from_usize(x) * dx.clone()))
+    }
+
+    /* A linspace grid per (start, end, n) entry; the result has shape [n0, n1, ..., rank]
+       and its last dimension holds the coordinates of each grid point */
+    pub fn grid(ranges: &[(T, T, usize)]) -> Tensor<T> {
+        let axes: Vec<Vec<T>> = ranges.iter()
+            .map(|(start, end, n)| Self::linspace(start.clone(), end.clone(), *n as u32).into_iter().collect())
+            .collect();
+        let mut shape: Vec<usize> = ranges.iter().map(|&(_, _, n)| n).collect();
+        shape.push(ranges.len());
+        Tensor::from_fn(&shape, |index| {
+            let d = index[ranges.len()];
+            axes[d][index[d]].clone()
+        })
+    }
+}
+
+impl<T: Clone + Neg<Output=T>> Neg for Tensor<T> {
+    type Output = Tensor<T>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|x| -x)
+    }
+}
+
+impl<T: Clone + Neg<Output=T>> Neg for &Tensor<T> {
+    type Output = Tensor<T>;
+
+    fn neg(self) -> Self::Output {
+        self.map(|x| -x)
+    }
+}
+
+/* Combines equally-shaped tensors element-wise for Sum and Product. Those traits can't
+   fail, so a shape mismatch panics, and an empty iterator gives the empty tensor */
+fn fold_tensors<T, I, F>(mut iter: I, f: F) -> Tensor<T>
+where T: Clone, I: Iterator<Item=Tensor<T>>, F: Fn(&Tensor<T>, &Tensor<T>) -> Result<Tensor<T>, TensorError>
+{
+    let Some(first) = iter.next() else {
+        return Tensor::default();
+    };
+    iter.fold(first.deep_clone(), |acc, t| {
+        if acc.shape != t.shape {
+            panic!("cannot combine tensors of shapes {:?} and {:?}", acc.shape, t.shape);
+        }
+        f(&acc, &t).unwrap()
+    })
+}
+
+impl<T: Clone + Add<Output=T>> Sum for Tensor<T> {
+    fn sum<I: Iterator<Item=Tensor<T>>>(iter: I) -> Self {
+        fold_tensors(iter, |a, b| a.add(b))
+    }
+}
+
+impl<'a, T: Clone + Add<Output=T>> Sum<&'a Tensor<T>> for Tensor<T> {
+    fn sum<I: Iterator<Item=&'a Tensor<T>>>(iter: I) -> Self {
+        fold_tensors(iter.cloned(), |a, b| a.add(b))
+    }
+}
+
+impl<T: Clone + Mul<Output=T>> Product for Tensor<T> {
+    fn product<I: Iterator<Item=Tensor<T>>>(iter: I) -> Self {
+        fold_tensors(iter, |a, b| a.mul(b))
+    }
+}
+
+impl<'a, T: Clone + Mul<Output=T>> Product<&'a Tensor<T>> for Tensor<T> {
+    fn product<I: Iterator<Item=&'a Tensor<T>>>(iter: I) -> Self {
+        fold_tensors(iter.cloned(), |a, b| a.mul(b))
+    }
+}
+
+// Lets scalars appear on the left, as in `2.0 * t` or `2.0 * &t`
+macro_rules! impl_scalar_lhs_ops {
+    ($($t:ty),*) => {$(
+        impl_scalar_lhs_ops!(@rhs $t, Tensor<$t>);
+        impl_scalar_lhs_ops!(@rhs $t, &Tensor<$t>);
+    )*};
+    (@rhs $t:ty, $rhs:ty) => {
+        impl Add<$rhs> for $t {
+            type Output = Tensor<$t>;
+
+            fn add(self, rhs: $rhs) -> Self::Output {
+                rhs.map(|x| self + x)
+            }
+        }
+
+        impl Sub<$rhs> for $t {
+            type Output = Tensor<$t>;
+
+            fn sub(self, rhs: $rhs) -> Self::Output {
+                rhs.map(|x| self - x)
+            }
+        }
+
+        impl Mul<$rhs> for $t {
+            type Output = Tensor<$t>;
+
+            fn mul(self, rhs: $rhs) -> Self::Output {
+                rhs.map(|x| self * x)
+            }
+        }
+
+        impl Div<$rhs> for $t {
+            type Output = Tensor<$t>;
+
+            fn div(self, rhs: $rhs) -> Self::Output {
+                rhs.map(|x| self / x)
+            }
+        }
+    };
+}
+
+impl_scalar_lhs_ops!(f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+// Integer arithmetic with the overflow behaviour chosen explicitly instead of by build profile
+macro_rules! impl_overflow_ops {
+    ($($t:ty),*) => {$(
+        impl Tensor<$t> {
+            pub fn add_saturating(&self, other: &Tensor<$t>) -> Result<Tensor<$t>, TensorError> {
+                self.zip_with(other, <$t>::saturating_add)
+            }
+
+            pub fn mul_saturating(&self, other: &Tensor<$t>) -> Result<Tensor<$t>, TensorError> {
+                self.zip_with(other, <$t>::saturating_mul)
+            }
+
+            pub fn add_wrapping(&self, other: &Tensor<$t>) -> Result<Tensor<$t>, TensorError> {
+                self.zip_with(other, <$t>::wrapping_add)
+            }
+
+            pub fn mul_wrapping(&self, other: &Tensor<$t>) -> Result<Tensor<$t>, TensorError> {
+                self.zip_with(other, <$t>::wrapping_mul)
+            }
+        }
+    )*};
+}
+
+impl_overflow_ops!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T> Tensor<T> where Standard: Distribution<T> {
+    pub fn rand(shape: &[usize]) -> Tensor<T> {
+        let (size, strides) = get_size_and_strides(shape);
+        let mut rng = rand::thread_rng();
+        Tensor {
+            data: Rc::new(RefCell::new((0..size).map(|_| rng.gen()).collect())),
+            base_index: 0,
+            size,
+            strides,
+            shape: shape.to_vec()
+        }
+    }
+}
+
+// Where NaNs land when ordering floats
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NanPolicy {
+    // max/min return NaN if any is present, sorting puts NaNs last like NumPy
+    #[default]
+    Propagate,
+    Smallest,
+    Largest
+}
+
+// NaN is the only value a float can't be compared with, itself included
+fn is_nan<T: PartialOrd>(x: &T) -> bool {
+    x.partial_cmp(x).is_none()
+}
+
+impl NanPolicy {
+    fn compare<T: PartialOrd>(self, a: &T, b: &T) -> Ordering {
+        match (is_nan(a), is_nan(b)) {
+            (false, false) => a.partial_cmp(b).unwrap(),
+            (true, true) => Ordering::Equal,
+            (true, false) => if self == NanPolicy::Smallest { Ordering::Less } else { Ordering::Greater },
+            (false, true) => if self == NanPolicy::Smallest { Ordering::Greater } else { Ordering::Less }
+        }
+    }
+}
+
+// An element-wise activation stored as data, e.g. in a layer's config
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Activation {
+    Relu,
+    Sigmoid,
+    Tanh,
+    // The tanh approximation
+    Gelu,
+    Softplus,
+    #[default]
+    Identity
+}
+
+// What normalize_sum does with a slice whose elements sum to zero
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ZeroSumPolicy {
+    #[default]
+    Error,
+    // Every element of the slice becomes 1 / len
+    Uniform
+}
+
+// Generic over PartialOrd so that f32, f64 and the half precision floats all get these
+impl<T: Clone + PartialOrd> Tensor<T> {
+    pub fn max(&self, policy: NanPolicy) -> Result<T, TensorError> {
+        self.into_iter().max_by(|a, b| policy.compare(a, b))
+            .ok_or_else(|| TensorError::new("cannot take the max of an empty tensor"))
+    }
+
+    pub fn min(&self, policy: NanPolicy) -> Result<T, TensorError> {
+        if policy == NanPolicy::Propagate {
+            if let Some(nan) = self.into_iter().find(is_nan) {
+                return Ok(nan);
+            }
+        }
+        self.into_iter().min_by(|a, b| policy.compare(a, b))
+            .ok_or_else(|| TensorError::new("cannot take the min of an empty tensor"))
+    }
+
+    pub fn sort_axis(&self, axis: impl Into<Axis>, policy: NanPolicy) -> Result<Tensor<T>, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        self.map_slices(axis, self.shape[axis], |slice| {
+            let mut sorted = slice.to_vec();
+            sorted.sort_by(|a, b| policy.compare(a, b));
+            sorted
+        })
+    }
+}
+
+impl Tensor<f64> {
+    // Central differences inside, one-sided at the two ends, like NumPy's gradient
+    pub fn gradient(&self, axis: impl Into<Axis>, spacing: f64) -> Result<Tensor<f64>, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        let n = self.shape[axis];
+        if n < 2 {
+            return Err(TensorError::new(format!("gradient needs at least 2 points along axis {}, got {}", axis, n)));
+        }
+        self.map_slices(axis, n, |y| {
+            (0..n).map(|i| match i {
+                0 => (y[1] - y[0]) / spacing,
+                i if i == n - 1 => (y[i] - y[i - 1]) / spacing,
+                i => (y[i + 1] - y[i - 1]) / (2.0 * spacing)
+            }).collect()
+        })
+    }
+
+    // Rescales the whole tensor so its L2 norm is at most max_norm
+    pub fn clip_grad_norm(&self, max_norm: f64) -> Tensor<f64> {
+        let norm = self.map(|x| x * x).sum().sqrt();
+        if norm <= max_norm {
+            return self.clone();
+        }
+        self.mul_scalar(max_norm / norm)
+    }
+
+    /* Inverted dropout: each element is 0 with probability p and 1 / (1 - p) otherwise,
+       so multiplying by the mask keeps the expected activation unchanged. The same seed
+       always gives the same mask */
+    pub fn dropout_mask(shape: &[usize], p: f64, seed: u64) -> Result<Tensor<f64>, TensorError> {
+        if !(0.0..1.0).contains(&p) {
+            return Err(TensorError::new(format!("dropout probability must be in [0, 1), got {}", p)));
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let scale = 1.0 / (1.0 - p);
+        let (size, _) = get_size_and_strides(shape);
+        Ok(Tensor::from_data((0..size).map(|_| if rng.gen::<f64>() < p { 0.0 } else { scale }).collect(), shape))
+    }
+
+    fn check_quantile(q: f64) -> Result<(), TensorError> {
+        if !(0.0..=1.0).contains(&q) {
+            return Err(TensorError::new(format!("quantile must be in [0, 1], got {}", q)));
+        }
+        Ok(())
+    }
+
+    // Linear interpolation between the two closest ranks, like NumPy's default; NaN if any value is
+    fn quantile_of(values: &[f64], q: f64) -> f64 {
+        if values.iter().any(|x| x.is_nan()) {
+            return f64::NAN;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let pos = q * (sorted.len() - 1) as f64;
+        let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+        sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+    }
+
+    pub fn quantile(&self, q: f64) -> Result<f64, TensorError> {
+        Self::check_quantile(q)?;
+        if self.size == 0 {
+            return Err(TensorError::new("cannot take the quantile of an empty tensor"));
+        }
+        let values: Vec<f64> = self.into_iter().collect();
+        Ok(Self::quantile_of(&values, q))
+    }
+
+    pub fn quantile_axis(&self, q: f64, axis: impl Into<Axis>) -> Result<Tensor<f64>, TensorError> {
+        let axis = axis.into().0;
+        Self::check_quantile(q)?;
+        self.check_axis(axis)?;
+        if self.shape[axis] == 0 {
+            return Err(TensorError::new(format!("cannot take the quantile along empty axis {}", axis)));
+        }
+        self.apply_along_axis(axis, |values| Self::quantile_of(values, q))
+    }
+
+    // Divides each slice along `axis` by its sum so it sums to 1, without exponentiating like softmax
+    pub fn normalize_sum(&self, axis: impl Into<Axis>, policy: ZeroSumPolicy) -> Result<Tensor<f64>, TensorError> {
+        let axis = axis.into().0;
+        self.check_axis(axis)?;
+        let mut zero_sum = false;
+        let result = self.map_slices(axis, self.shape[axis], |slice| {
+            let total: f64 = slice.iter().sum();
+            if total == 0.0 {
+                zero_sum = true;
+                return vec![1.0 / slice.len() as f64; slice.len()];
+            }
+            slice.iter().map(|x| x / total).collect()
+        })?;
+        if zero_sum && policy == ZeroSumPolicy::Error {
+            return Err(TensorError::new(format!("cannot normalize a slice along axis {} that sums to zero", axis)));
+        }
+        Ok(result)
+    }
+
+    // Stops at the first element matching `pred`, walking the view's own strides
+    fn any_element<F: Fn(f64) -> bool>(&self, pred: F) -> bool {
+        let data = self.data.borrow();
+        TensorIndexIterator::new(self.clone()).any(|i| pred(data[i]))
+    }
+
+    pub fn has_nan(&self) -> bool {
+        self.any_element(f64::is_nan)
+    }
+
+    pub fn has_inf(&self) -> bool {
+        self.any_element(f64::is_infinite)
+    }
+
+    pub fn is_finite(&self) -> bool {
+        !self.any_element(|x| !x.is_finite())
+    }
+
+    pub fn nan_to_num(&self, nan: f64, posinf: f64, neginf: f64) -> Tensor<f64> {
+        self.map(|x| match x {
+            x if x.is_nan() => nan,
+            f64::INFINITY => posinf,
+            f64::NEG_INFINITY => neginf,
+            x => x
+        })
+    }
+
+    // Scaled dot-product scores q·kᵀ / sqrt(d) for [b, seq, d] inputs, giving [b, seq_q, seq_k]
+    pub fn attention_scores(q: &Tensor<f64>, k: &Tensor<f64>) -> Result<Tensor<f64>, TensorError> {
+        if q.rank() != 3 || k.rank() != 3 || q.shape[0] != k.shape[0] || q.shape[2] != k.shape[2] {
+            return Err(TensorError::new(format!("attention needs [b, seq, d] queries and keys with matching b and d, got {:?} and {:?}", q.shape, k.shape)));
+        }
+        let d = q.shape[2] as f64;
+        Ok(q.bmm(&k.transpose(1, 2)?)?.div_scalar(d.sqrt()))
+    }
+
+    // Mean of each run of `window` consecutive elements, keeping a running sum rather than re-adding windows
+    pub fn moving_average(&self, window: usize) -> Result<Tensor<f64>, TensorError> {
+        if self.rank() != 1 {
+            return Err(TensorError::new(format!("moving_average requires a rank-1 tensor, got rank {}", self.rank())));
+        }
+        if window == 0 || window > self.size {
+            return Err(TensorError::new(format!("window {} does not fit a tensor of length {}", window, self.size)));
+        }
+        let values: Vec<f64> = self.into_iter().collect();
+        let mut sum: f64 = values[..window].iter().sum();
+        let mut result = Vec::with_capacity(values.len() - window + 1);
+        result.push(sum / window as f64);
+        for i in window..values.len() {
+            sum += values[i] - values[i - window];
+            result.push(sum / window as f64);
+        }
+        Ok(Tensor::from_data(result, &[values.len() - window + 1]))
+    }
+
+    pub fn sigmoid(&self) -> Tensor<f64> {
+        self.map(|x| 1.0 / (1.0 + (-x).exp()))
+    }
+
+    pub fn tanh(&self) -> Tensor<f64> {
+        self.map(f64::tanh)
+    }
+
+    pub fn gelu(&self) -> Tensor<f64> {
+        let c = (2.0 / std::f64::consts::PI).sqrt();
+        self.map(|x| 0.5 * x * (1.0 + (c * (x + 0.044715 * x * x * x)).tanh()))
+    }
+
+    // ln(1 + e^x), rearranged so large inputs don't overflow
+    pub fn softplus(&self) -> Tensor<f64> {
+        self.map(|x| x.max(0.0) + (-x.abs()).exp().ln_1p())
+    }
+
+    pub fn activate(&self, act: Activation) -> Tensor<f64> {
+        match act {
+            Activation::Relu => self.relu(),
+            Activation::Sigmoid => self.sigmoid(),
+            Activation::Tanh => self.tanh(),
+            Activation::Gelu => self.gelu(),
+            Activation::Softplus => self.softplus(),
+            Activation::Identity => self.clone()
+        }
+    }
+
+    /* A memoization key from the shape and the bit patterns of the elements in logical order,
+       so views with equal contents hash alike. It is FNV-1a, fixed across runs and Rust
+       versions. Comparison is bit-level: -0.0 and 0.0 hash differently, and NaNs with
+       different payloads do too */
+    pub fn content_hash(&self) -> u64 {
+        const OFFSET: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+        let mix = |hash: u64, word: u64| word.to_le_bytes().iter().fold(hash, |h, &b| (h ^ b as u64).wrapping_mul(PRIME));
+        let mut hash = mix(OFFSET, self.rank() as u64);
+        for &n in &self.shape {
+            hash = mix(hash, n as u64);
+        }
+        self.for_each_ref(|x| hash = mix(hash, x.to_bits()));
+        hash
+    }
+
+    // Affine quantization: round(x / scale) + zero_point, saturating to the u8 range
+    pub fn quantize(&self, scale: f64, zero_point: u8) -> Tensor<u8> {
+        self.map(|x| ((x / scale).round() + zero_point as f64).clamp(0.0, 255.0) as u8)
+    }
+
+    pub fn quantize_auto(&self) -> (Tensor<u8>, f64, u8) {
+        // The range always includes 0 so that zero is exactly representable
+        let (min, max) = self.into_iter().fold((0.0, 0.0), |(lo, hi): (f64, f64), x| (lo.min(x), hi.max(x)));
+        let scale = if max > min { (max - min) / 255.0 } else { 1.0 };
+        let zero_point = (-min / scale).round().clamp(0.0, 255.0) as u8;
+        (self.quantize(scale, zero_point), scale, zero_point)
+    }
+
+    // Returns the lower-triangular L with L·Lᵀ = self, only the lower triangle is read
+    pub fn cholesky(&self) -> Result<Tensor<f64>, TensorError> {
+        let n = self.check_square("cholesky")?;
+        let mut l = vec![0.0; n * n];
+        for i in 0..n {
+            for j in 0..=i {
+                let mut sum = self.get_value(&[i, j])?;
+                for k in 0..j {
+                    sum -= l[i * n + k] * l[j * n + k];
+                }
+                if i == j {
+                    if sum.is_nan() || sum <= 0.0 {
+                        return Err(TensorError::new("matrix is not positive-definite"));
+                    }
+                    l[i * n + i] = sum.sqrt();
+                } else {
+                    l[i * n + j] = sum / l[j * n + j];
+                }
+            }
+        }
+        Ok(Tensor::from_data(l, &[n, n]))
+    }
+
+    // Doolittle LU with partial pivoting, also counting the row swaps for the determinant
+    fn lu_parts(&self) -> Result<(Vec<f64>, Vec<usize>, usize), TensorError> {
+        let n = self.check_square("lu")?;
+        let mut lu: Vec<f64> = self.into_iter().collect();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        // Pivots this small relative to the matrix are rounding noise
+        let tol = lu.iter().fold(0.0, |m: f64, x| m.max(x.abs())) * n as f64 * f64::EPSILON;
+        for k in 0..n {
+            let p = (k..n).max_by(|&a, &b| lu[a * n + k].abs().total_cmp(&lu[b * n + k].abs())).unwrap();
+            if lu[p * n + k].abs() <= tol {
+                return Err(TensorError::new("matrix is singular"));
+            }
+            if p != k {
+                for j in 0..n {
+                    lu.swap(p * n + j, k * n + j);
+                }
+                perm.swap(p, k);
+                swaps += 1;
+            }
+            for i in k + 1..n {
+                let factor = lu[i * n + k] / lu[k * n + k];
+                lu[i * n + k] = factor;
+                for j in k + 1..n {
+                    lu[i * n + j] -= factor * lu[k * n + j];
+                }
+            }
+        }
+        Ok((lu, perm, swaps))
+    }
+
+    /* Returns L and U packed into one matrix (L's unit diagonal is implied) along
+       with the permutation: row i of the factored matrix is row perm[i] of self */
+    pub fn lu(&self) -> Result<(Tensor<f64>, Tensor<usize>), TensorError> {
+        let (lu, perm, _) = self.lu_parts()?;
+        let n = perm.len();
+        Ok((Tensor::from_data(lu, &[n, n]), Tensor::from_data(perm, &[n])))
+    }
+
+    // Solves self·x = b where b is either a vector or a matrix of right-hand sides
+    pub fn solve(&self, b: &Tensor<f64>) -> Result<Tensor<f64>, TensorError> {
+        let (lu, perm, _) = self.lu_parts()?;
+        let n = perm.len();
+        if b.rank() == 0 || b.rank() > 2 || b.shape[0] != n {
+            return Err(TensorError::new(format!("cannot solve a {0}x{0} system with a right-hand side of shape {1:?}", n, b.shape)));
+        }
+        let k = if b.rank() == 2 { b.shape[1] } else { 1 };
+        let rhs: Vec<f64> = b.into_iter().collect();
+        let mut x = vec![0.0; n * k];
+        let mut y = vec![0.0; n];
+        for c in 0..k {
+            // Forward substitution through L on the permuted right-hand side
+            for i in 0..n {
+                let mut sum = rhs[perm[i] * k + c];
+                for j in 0..i {
+                    sum -= lu[i * n + j] * y[j];
+                }
+                y[i] = sum;
+            }
+            // Back substitution through U
+            for i in (0..n).rev() {
+                let mut sum = y[i];
+                for j in i + 1..n {
+                    sum -= lu[i * n + j] * x[j * k + c];
+                }
+                x[i * k + c] = sum / lu[i * n + i];
+            }
+        }
+        Ok(Tensor::from_data(x, &b.shape))
+    }
+
+    pub fn det(&self) -> Result<f64, TensorError> {
+        let (lu, perm, swaps) = self.lu_parts()?;
+        let n = perm.len();
+        let sign = if swaps % 2 == 0 { 1.0 } else { -1.0 };
+        Ok((0..n).fold(sign, |acc, i| acc * lu[i * n + i]))
+    }
+
+    pub fn inv(&self) -> Result<Tensor<f64>, TensorError> {
+        let n = self.check_square("inv")?;
+        self.solve(&Tensor::eye(n))
+    }
+
+    // Euclidean distance between every row of self ([m, d]) and every row of other ([n, d])
+    pub fn pairwise_dist(&self, other: &Tensor<f64>) -> Result<Tensor<f64>, TensorError> {
+        if self.rank() != 2 || other.rank() != 2 || self.shape[1] != other.shape[1] {
+            return Err(TensorError::new(format!("pairwise_dist requires [m, d] and [n, d] tensors, got {:?} and {:?}", self.shape, other.shape)));
+        }
+        let mut distances = Vec::with_capacity(self.shape[0] * other.shape[0]);
+        for a in self.rows() {
+            for b in other.rows() {
+                distances.push(a.sub(&b)?.into_iter().map(|x| x * x).sum::<f64>().sqrt());
+            }
+        }
+        Ok(Tensor::from_data(distances, &[self.shape[0], other.shape[0]]))
+    }
+
+    pub fn interp_linear(&self, new_len: usize) -> Result<Tensor<f64>, TensorError> {
+        if self.rank() != 1 || self.size < 2 {
+            return Err(TensorError::new("interp_linear requires a rank-1 tensor with at least 2 elements"));
+        }
+        let values: Vec<f64> = self.into_iter().collect();
+        let last = values.len() - 1;
+        // The endpoints line up, so output i maps back to source position i * step
+        let step = if new_len > 1 { last as f64 / (new_len - 1) as f64 } else { 0.0 };
+        Ok(Tensor::from_iter((0..new_len).map(|i| {
+            let x = i as f64 * step;
+            let j = (x.floor() as usize).min(last - 1);
+            let t = x - j as f64;
+            values[j] * (1.0 - t) + values[j + 1] * t
+        })))
+    }
+}
+
+#[cfg(feature = "complex")]
+impl Tensor<Complex<f64>> {
+    pub fn conj(&self) -> Tensor<Complex<f64>> {
+        self.map(|z| z.conj())
+    }
+
+    pub fn real(&self) -> Tensor<f64> {
+        self.map(|z| z.re)
+    }
+
+    pub fn imag(&self) -> Tensor<f64> {
+        self.map(|z| z.im)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl Tensor<f64> {
+    // A [rows, cols] tensor from a CSV file of numbers, optionally skipping a header row
+    pub fn from_csv(path: &Path, has_header: bool) -> Result<Tensor<f64>, TensorError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(has_header)
+            .flexible(true)
+            .from_path(path)
+            .map_err(|e| TensorError::new(format!("could not open {}", path.display())).with_source(e))?;
+        let mut data = Vec::new();
+        let mut cols = None;
+        let mut rows = 0;
+        for record in reader.records() {
+            let record = record.map_err(|e| TensorError::new(format!("could not read {}", path.display())).with_source(e))?;
+            match cols {
+                None => cols = Some(record.len()),
+                Some(n) if n != record.len() => {
+                    return Err(TensorError::new(format!("row {} has {} columns, expected {}", rows, record.len(), n)));
+                },
+                _ => {}
+            }
+            for (col, field) in record.iter().enumerate() {
+                let value = field.trim().parse::<f64>()
+                    .map_err(|e| TensorError::new(format!("could not parse {:?} at row {}, column {}", field, rows, col)).with_source(e))?;
+                data.push(value);
+            }
+            rows += 1;
+        }
+        Ok(Tensor::from_data(data, &[rows, cols.unwrap_or(0)]))
+    }
+
+    pub fn to_csv(&self, path: &Path) -> Result<(), TensorError> {
+        if self.rank() != 2 {
+            return Err(TensorError::new(format!("to_csv requires a rank-2 tensor, got rank {}", self.rank())));
+        }
+        let mut writer = csv::Writer::from_path(path)
+            .map_err(|e| TensorError::new(format!("could not create {}", path.display())).with_source(e))?;
+        for row in self.rows() {
+            writer.write_record(row.into_iter().map(|x| x.to_string()))
+                .map_err(|e| TensorError::new(format!("could not write {}", path.display())).with_source(e))?;
+        }
+        writer.flush().map_err(|e| TensorError::new(format!("could not write {}", path.display())).with_source(e))
+    }
+}
+
+#[cfg(feature = "simd")]
+impl Tensor<f32> {
+    /* map() eight lanes at a time. The tail is padded with zeros to fill a final
+       vector, so f sees those padding lanes too but their results are dropped */
+    pub fn map_simd<F: Fn(f32x8) -> f32x8>(&self, f: F) -> Tensor<f32> {
+        let src = self.contiguous();
+        let data = src.data.borrow();
+        let values = &data[src.base_index..src.base_index + src.size];
+        let mut out = Vec::with_capacity(src.size);
+        let mut chunks = values.chunks_exact(8);
+        for chunk in &mut chunks {
+            let lanes: [f32; 8] = chunk.try_into().unwrap();
+            out.extend_from_slice(&f(f32x8::from(lanes)).to_array());
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut lanes = [0.0; 8];
+            lanes[..rest.len()].copy_from_slice(rest);
+            out.extend_from_slice(&f(f32x8::from(lanes)).to_array()[..rest.len()]);
+        }
+        Tensor::from_data(out, &self.shape)
+    }
+}
+
+impl Tensor<usize> {
+    // How often each value 0..=max appears, padded with zeros to at least min_length entries
+    pub fn bincount(&self, min_length: usize) -> Result<Tensor<usize>, TensorError> {
+        if self.rank() != 1 {
+            return Err(TensorError::new(format!("bincount requires a rank-1 tensor, got rank {}", self.rank())));
+        }
+        let len = self.into_iter().max().map_or(0, |max| max + 1).max(min_length);
+        let mut counts = vec![0; len];
+        self.for_each_ref(|&x| counts[x] += 1);
+        Ok(Tensor::from_data(counts, &[len]))
+    }
+}
+
+impl Tensor<u8> {
+    pub fn dequantize(&self, scale: f64, zero_point: u8) -> Tensor<f64> {
+        self.map(|q| (q as f64 - zero_point as f64) * scale)
+    }
+}
+
+/* Accumulates the mean and variance of every element fed to it using
+   Welford's algorithm, so a stream of tensors never has to be stored */
+#[derive(Clone, Debug)]
+pub struct RunningStats<T> {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    marker: PhantomData<T>
+}
+
+impl<T> RunningStats<T> {
+    pub fn new() -> RunningStats<T> {
+        RunningStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            marker: PhantomData
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        self.mean
+    }
+
+    // Population variance of everything seen so far
+    pub fn var(&self) -> f64 {
+        if self.count == 0 {
+            return f64::NAN;
+        }
+        self.m2 / self.count as f64
+    }
+
+    pub fn std(&self) -> f64 {
+        self.var().sqrt()
+    }
+}
+
+impl<T: Clone + Into<f64>> RunningStats<T> {
+    pub fn update(&mut self, t: &Tensor<T>) {
+        for v in t {
+            let x: f64 = v.into();
+            self.count += 1;
+            let delta = x - self.mean;
+            self.mean += delta / self.count as f64;
+            self.m2 += delta * (x - self.mean);
+        }
+    }
+}
+
+impl<T> Default for RunningStats<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/* Per-column minimum and maximum of [rows, features] batches seen so far, used to
+   scale each feature into [0, 1]. A feature that has only ever had one value maps to 0 */
+#[derive(Clone, Debug, Default)]
+pub struct MinMaxScaler {
+    min: Option<Tensor<f64>>,
+    max: Option<Tensor<f64>>
+}
+
+impl MinMaxScaler {
+    pub fn new() -> MinMaxScaler {
+        MinMaxScaler {
+            min: None,
+            max: None
+        }
+    }
+
+    fn check_features(&self, t: &Tensor<f64>) -> Result<(), TensorError> {
+        if t.rank() != 2 {
+            return Err(TensorError::new(format!("MinMaxScaler expects a [rows, features] tensor, got shape {:?}", t.shape)));
+        }
+        if let Some(min) = &self.min {
+            if min.shape[0] != t.shape[1] {
+                return Err(TensorError::new(format!("MinMaxScaler was fitted on {} features, got {}", min.shape[0], t.shape[1])));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn partial_fit(&mut self, t: &Tensor<f64>) -> Result<(), TensorError> {
+        self.check_features(t)?;
+        if t.shape[0] == 0 {
+            return Ok(());
+        }
+        let batch_min = t.apply_along_axis(0, |col| col.iter().copied().fold(f64::INFINITY, f64::min))?;
+        let batch_max = t.apply_along_axis(0, |col| col.iter().copied().fold(f64::NEG_INFINITY, f64::max))?;
+        self.min = Some(match self.min.take() {
+            Some(min) => min.zip_with(&batch_min, f64::min)?,
+            None => batch_min
+        });
+        self.max = Some(match self.max.take() {
+            Some(max) => max.zip_with(&batch_max, f64::max)?,
+            None => batch_max
+        });
+        Ok(())
+    }
+
+    pub fn transform(&self, t: &Tensor<f64>) -> Result<Tensor<f64>, TensorError> {
+        self.check_features(t)?;
+        let (Some(min), Some(max)) = (&self.min, &self.max) else {
+            return Err(TensorError::new("MinMaxScaler has not been fitted yet"));
+        };
+        let range = max.sub(min)?;
+        t.sub(min)?.zip_with(&range, |x, r| if r == 0.0 { 0.0 } else { x / r })
+    }
+}
+
+impl<T: Display> Display for Tensor<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data = self.data.borrow();
+
+        /* With a precision, as in {:.2}, every element is printed to it and right-aligned
+           to the widest one so columns line up. Types that ignore precision (integers)
+           still get aligned */
+        let cells: Vec<String> = TensorIndexIterator::new(self.clone()).map(|i| match f.precision() {
+            Some(precision) => format!("{:.*}", precision, data[i]),
+            None => format!("{}", data[i])
+        }).collect();
+        let width = match f.precision() {
+            Some(_) => cells.iter().map(|c| c.chars().count()).max().unwrap_or(0),
+            None => 0
+        };
+
+        if self.is_scalar() {
+            write!(f, "{}", cells[0])?;
+            return Ok(());
+        }
+
+        // Brackets follow the logical layout, which a view's strides may not
+        let (_, strides) = get_size_and_strides(&self.shape);
         let mut depth = 0;
-        for i in 0..self.size {
+        for (i, cell) in cells.iter().enumerate() {
             let mut open = if i == 0 { 1 } else { 0 };
             let mut close = if i + 1 == self.size { 1 } else { 0 };
-            for s in &self.strides[..self.strides.len() - 1] {
+            for s in &strides[..strides.len() - 1] {
                 if i % s == 0 {
                     open += 1;
                 }
             }
-            for s in &self.strides[..self.strides.len() - 1] {
+            for s in &strides[..strides.len() - 1] {
                 if (i + 1) % s == 0 {
                     close += 1;
                 }
@@ -195,7 +2523,7 @@ impl<T: Display> Display for Tensor<T> {
             for _ in 0..open {
                 write!(f, "[")?;
             }
-            write!(f, "{}", data[self.base_index + i])?;
+            write!(f, "{:>width$}", cell)?;
             if close == 0 {
                 write!(f, ", ")?;
             }
@@ -204,7 +2532,7 @@ impl<T: Display> Display for Tensor<T> {
             }
             depth += open - close;
             if close > 0 && depth > 0 {
-                write!(f, "\n")?;
+                writeln!(f)?;
                 for _ in 0..depth {
                     write!(f, " ")?;
                 }
@@ -214,6 +2542,49 @@ impl<T: Display> Display for Tensor<T> {
     }
 }
 
+// Same shape and same elements in logical order; layout and sharing don't matter
+impl<T: PartialEq> PartialEq for Tensor<T> {
+    fn eq(&self, other: &Tensor<T>) -> bool {
+        if self.shape != other.shape {
+            return false;
+        }
+        let (lhs, rhs) = (self.data.borrow(), other.data.borrow());
+        // Two contiguous buffers can be compared as plain slices
+        if self.is_contiguous() && other.is_contiguous() {
+            return lhs[self.base_index..self.base_index + self.size] == rhs[other.base_index..other.base_index + other.size];
+        }
+        TensorIndexIterator::new(self.clone())
+            .zip(TensorIndexIterator::new(other.clone()))
+            .all(|(i, j)| lhs[i] == rhs[j])
+    }
+}
+
+// Layout first, then the first few elements in logical order, so dbg!() stays readable
+impl<T: Debug> Debug for Tensor<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const PREVIEW: usize = 8;
+
+        struct Preview<'a, T>(Vec<&'a T>, bool);
+        impl<T: Debug> Debug for Preview<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let mut list = f.debug_list();
+                list.entries(&self.0);
+                if self.1 { list.finish_non_exhaustive() } else { list.finish() }
+            }
+        }
+
+        let data = self.data.borrow();
+        let values = TensorIndexIterator::new(self.clone()).take(PREVIEW).map(|i| &data[i]).collect();
+        f.debug_struct("Tensor")
+            .field("shape", &self.shape)
+            .field("strides", &self.strides)
+            .field("base_index", &self.base_index)
+            .field("contiguous", &self.is_contiguous())
+            .field("data", &Preview(values, self.size > PREVIEW))
+            .finish()
+    }
+}
+
 /* This is used internally to efficiently iterate through the
    data indices of a tensor in the correct order */
 struct TensorIndexIterator<T> {
@@ -226,12 +2597,17 @@ struct TensorIndexIterator<T> {
 impl<T> TensorIndexIterator<T> {
     fn new(tensor: Tensor<T>) -> TensorIndexIterator<T> {
         TensorIndexIterator {
-            index: vec![0 as usize; tensor.rank()],
+            index: vec![0; tensor.rank()],
             data_index: tensor.base_index,
-            tensor: tensor,
-            is_done: false
+            is_done: tensor.size == 0,
+            tensor
         }
     }
+
+    // The coordinate of the element the next call to next() will return
+    fn index(&self) -> &[usize] {
+        &self.index
+    }
 }
 
 impl<T> Iterator for TensorIndexIterator<T> {
@@ -242,6 +2618,9 @@ impl<T> Iterator for TensorIndexIterator<T> {
             return None;
         }
         let result = self.data_index;
+        if self.tensor.is_scalar() {
+            self.is_done = true;
+        }
         for d in (0..self.tensor.rank()).rev() {
             self.index[d] += 1;
             self.data_index += self.tensor.strides[d];
@@ -263,7 +2642,7 @@ pub struct TensorIterator<T> {
     index_iterator: TensorIndexIterator<T>
 }
 
-impl<'a, T> TensorIterator<T> {
+impl<T> TensorIterator<T> {
     pub fn new(tensor: Tensor<T>) -> TensorIterator<T> {
         TensorIterator {
             index_iterator: TensorIndexIterator::new(tensor)
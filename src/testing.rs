@@ -0,0 +1,29 @@
+use std::fmt::Debug;
+
+use crate::tensor::Tensor;
+
+#[track_caller]
+pub fn assert_tensor_eq<T: PartialEq + Debug + Clone>(a: &Tensor<T>, b: &Tensor<T>) {
+    if a.shape() != b.shape() {
+        panic!("tensor shapes differ: left {:?}, right {:?}", a.shape(), b.shape());
+    }
+    for (i, (x, y)) in a.into_iter().zip(b).enumerate() {
+        if x != y {
+            panic!("tensors differ at index {:?}: left {:?}, right {:?}", a.unravel_index(i).unwrap(), x, y);
+        }
+    }
+}
+
+#[track_caller]
+pub fn assert_tensor_close(a: &Tensor<f64>, b: &Tensor<f64>, tol: f64) {
+    if a.shape() != b.shape() {
+        panic!("tensor shapes differ: left {:?}, right {:?}", a.shape(), b.shape());
+    }
+    for (i, (x, y)) in a.into_iter().zip(b).enumerate() {
+        // NaNs are never close to anything, matching infinities are
+        let close = x == y || (x - y).abs() <= tol;
+        if !close {
+            panic!("tensors differ at index {:?} by more than {}: left {:?}, right {:?}", a.unravel_index(i).unwrap(), tol, x, y);
+        }
+    }
+}
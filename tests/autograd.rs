@@ -0,0 +1,38 @@
+use crabtorch::Tensor;
+use crabtorch::autograd::Variable;
+use crabtorch::testing::assert_tensor_close;
+
+#[test]
+fn grad_of_sum_of_squares() {
+    let x = Variable::new(Tensor::from_array(&[1.0, -2.0, 3.0]));
+    let y = x.mul(&x).unwrap().sum();
+    y.backward().unwrap();
+    assert_tensor_close(&x.grad().unwrap(), &Tensor::from_array(&[2.0, -4.0, 6.0]), 1e-12);
+}
+
+#[test]
+fn grad_through_matmul_and_a_broadcast_add() {
+    let w = Variable::new(Tensor::from_array(&[1.0, 2.0, 3.0, 4.0]).reshape(&[2, 2]).unwrap());
+    let a = Variable::new(Tensor::from_array(&[1.0, 1.0]).reshape(&[1, 2]).unwrap());
+    let b = Variable::new(Tensor::from_array(&[0.5, 0.5]));
+    a.matmul(&w).unwrap().add(&b).unwrap().sum().backward().unwrap();
+    // d/dw = aᵀ·ones(1, 2), d/da = ones(1, 2)·wᵀ, and b was broadcast so it gets the summed gradient
+    assert_tensor_close(&w.grad().unwrap(), &Tensor::ones(&[2, 2]), 1e-12);
+    assert_tensor_close(&a.grad().unwrap(), &Tensor::from_array(&[3.0, 7.0]).reshape(&[1, 2]).unwrap(), 1e-12);
+    assert_tensor_close(&b.grad().unwrap(), &Tensor::from_array(&[1.0, 1.0]), 1e-12);
+}
+
+#[test]
+fn a_reused_variable_accumulates_every_path() {
+    let x = Variable::new(Tensor::from_array(&[2.0]));
+    x.mul(&x).unwrap().add(&x).unwrap().sum().backward().unwrap();
+    assert_tensor_close(&x.grad().unwrap(), &Tensor::from_array(&[5.0]), 1e-12);
+    x.zero_grad();
+    assert!(x.grad().is_none());
+}
+
+#[test]
+fn variable_matmul_needs_matrices() {
+    let v = Variable::new(Tensor::ones(&[2, 2, 2]));
+    assert!(v.matmul(&v).is_err());
+}
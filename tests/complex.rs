@@ -0,0 +1,27 @@
+#![cfg(feature = "complex")]
+
+use num_complex::Complex;
+
+use crabtorch::Tensor;
+
+fn c(re: f64, im: f64) -> Complex<f64> {
+    Complex::new(re, im)
+}
+
+#[test]
+fn conj_negates_the_imaginary_part() {
+    let a = Tensor::from_array(&[c(1.0, 1.0), c(0.0, 2.0), c(1.0, -3.0)]);
+    assert_eq!(a.conj().imag().into_iter().collect::<Vec<_>>(), vec![-1.0, -2.0, 3.0]);
+    assert_eq!(a.conj().real().into_iter().collect::<Vec<_>>(), vec![1.0, 0.0, 1.0]);
+}
+
+#[test]
+fn complex_matmul() {
+    let a = Tensor::from_array(&[c(1.0, 1.0), c(0.0, 2.0), c(1.0, 0.0), c(0.0, 0.0)]).reshape(&[2, 2]).unwrap();
+    let m = a.matmul(&a.conj()).unwrap();
+    // (1+i)(1-i) + 2i·1 and (1+i)(-2i) + 2i·0
+    assert_eq!(m.get_value(&[0, 0]).unwrap(), c(2.0, 2.0));
+    assert_eq!(m.get_value(&[0, 1]).unwrap(), c(2.0, -2.0));
+    assert_eq!(m.get_value(&[1, 0]).unwrap(), c(1.0, -1.0));
+    assert_eq!(m.get_value(&[1, 1]).unwrap(), c(0.0, -2.0));
+}
@@ -0,0 +1,207 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use crabtorch::{Numeric, Tensor};
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn default_is_empty() {
+    let t = Tensor::<f64>::default();
+    assert_eq!(t.size(), 0);
+    assert_eq!(t.shape(), &[0]);
+    assert_eq!(t.into_iter().count(), 0);
+}
+
+#[test]
+fn meshgrid_uses_ij_indexing() {
+    let (xs, ys) = Tensor::meshgrid(&Tensor::from_array(&[1, 2]), &Tensor::from_array(&[10, 20, 30])).unwrap();
+    assert_eq!(format!("{}", xs), "[[1, 1, 1]\n [2, 2, 2]]");
+    assert_eq!(format!("{}", ys), "[[10, 20, 30]\n [10, 20, 30]]");
+}
+
+#[test]
+fn map_rows_collects_the_results() {
+    let t = Tensor::from_iter(0..6).reshape(&[3, 2]).unwrap();
+    let sums = t.map_rows(|row| Tensor::scalar(row.sum())).unwrap();
+    assert_eq!(sums.shape(), &[3]);
+    assert_eq!(sums.into_iter().collect::<Vec<_>>(), vec![1, 5, 9]);
+    assert!(t.map_rows(|row| if row.sum() > 2 { row } else { Tensor::scalar(0) }).is_err());
+}
+
+#[test]
+fn map_rows_without_rows_is_empty() {
+    let t: Tensor<i32> = Tensor::zeros(&[0, 4]);
+    let out = t.map_rows(|row| Tensor::scalar(row.sum())).unwrap();
+    assert_eq!(out.shape(), &[0]);
+    assert_eq!(out.size(), 0);
+}
+
+#[test]
+fn stack_inserts_a_new_axis() {
+    let a = Tensor::from_iter(0..4).reshape(&[2, 2]).unwrap();
+    let b = a.mul_scalar(10);
+    let s = Tensor::stack(&[a.clone(), b.clone()], 1).unwrap();
+    assert_eq!(s.shape(), &[2, 2, 2]);
+    assert_eq!(s.into_iter().collect::<Vec<_>>(), vec![0, 1, 0, 10, 2, 3, 20, 30]);
+    let s = Tensor::stack(&[a, b], 2).unwrap();
+    assert_eq!(s.into_iter().collect::<Vec<_>>(), vec![0, 0, 1, 10, 2, 20, 3, 30]);
+}
+
+#[test]
+fn from_fn_builds_the_identity() {
+    let i = Tensor::from_fn(&[3, 3], |idx| if idx[0] == idx[1] { 1.0 } else { 0.0 });
+    assert_tensor_eq(&i, &Tensor::eye(3));
+    let t = Tensor::from_fn(&[2, 3], |idx| idx[0] * 10 + idx[1]);
+    assert_eq!(t.into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 10, 11, 12]);
+    assert_eq!(Tensor::from_fn(&[], |_| 7).into_iter().collect::<Vec<_>>(), vec![7]);
+}
+
+// Two decimal places of fixed point, standing in for a user-defined number type
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+struct Fixed(i64);
+
+impl From<bool> for Fixed {
+    fn from(x: bool) -> Fixed {
+        Fixed(x as i64 * 100)
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, other: Fixed) -> Fixed {
+        Fixed(self.0 + other.0)
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, other: Fixed) -> Fixed {
+        Fixed(self.0 - other.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, other: Fixed) -> Fixed {
+        Fixed(self.0 * other.0 / 100)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Fixed;
+
+    fn div(self, other: Fixed) -> Fixed {
+        Fixed(self.0 * 100 / other.0)
+    }
+}
+
+#[test]
+fn linspace_with_a_user_numeric_type() {
+    let l = Tensor::linspace(Fixed(0), Fixed(200), 4);
+    assert_eq!(l.into_iter().collect::<Vec<_>>(), vec![Fixed(0), Fixed(50), Fixed(100), Fixed(150)]);
+}
+
+#[test]
+fn every_primitive_is_numeric() {
+    fn numeric<T: Numeric>() {}
+    numeric::<f32>();
+    numeric::<f64>();
+    numeric::<i8>();
+    numeric::<i64>();
+    numeric::<u8>();
+    numeric::<usize>();
+    assert_eq!(Tensor::<f32>::linspace(0.0, 1.0, 4).into_iter().collect::<Vec<_>>(), vec![0.0, 0.25, 0.5, 0.75]);
+    assert_eq!(Tensor::from_array(&[1, 2, 3, 4]).avg_pool1d(2, 2).unwrap().into_iter().collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(Tensor::<f64>::rand(&[2]).shape(), &[2]);
+}
+
+#[test]
+fn from_usize() {
+    assert_eq!(f64::from_usize(300), 300.0);
+    assert_eq!(u8::from_usize(0), 0);
+    assert_eq!(Fixed::from_usize(5), Fixed(500));
+}
+
+#[test]
+fn grid_points() {
+    let g = Tensor::grid(&[(0.0, 1.0, 2), (10.0, 13.0, 3)]);
+    assert_eq!(g.shape(), &[2, 3, 2]);
+    assert_eq!(g.get(&[0, 0]).unwrap().into_iter().collect::<Vec<_>>(), vec![0.0, 10.0]);
+    assert_eq!(g.get(&[0, 1]).unwrap().into_iter().collect::<Vec<_>>(), vec![0.0, 11.0]);
+    assert_eq!(g.get(&[1, 2]).unwrap().into_iter().collect::<Vec<_>>(), vec![0.5, 12.0]);
+}
+
+#[test]
+fn repeat_along_copies_the_whole_tensor() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    let r = a.repeat_along(0, 3).unwrap();
+    assert_eq!(r.shape(), &[6, 3]);
+    assert_tensor_eq(&r.slice(&[4..6, 0..3]).unwrap(), &a);
+    assert_tensor_eq(&a.repeat_along(1, 2).unwrap(), &Tensor::from_array(&[0, 1, 2, 0, 1, 2, 3, 4, 5, 3, 4, 5]).reshape(&[2, 6]).unwrap());
+    assert!(a.repeat_along(2, 2).is_err());
+    assert!(a.repeat_along(0, 0).is_err());
+}
+
+#[test]
+fn concat_along_an_existing_axis() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    let b: Tensor<u32> = Tensor::from_shape(9, &[2, 1]);
+    assert_tensor_eq(&Tensor::concat(&[a.clone(), b.clone()], 1).unwrap(), &Tensor::from_array(&[0, 1, 2, 9, 3, 4, 5, 9]).reshape(&[2, 4]).unwrap());
+    assert!(Tensor::concat(&[a.clone(), b], 0).is_err());
+    let empty: Tensor<u32> = Tensor::from_fn(&[0, 3], |_| 0);
+    assert_tensor_eq(&Tensor::concat(&[empty, a.clone()], 0).unwrap(), &a);
+}
+
+#[test]
+fn from_coo_builds_the_identity() {
+    let t: Tensor<f64> = Tensor::from_coo(&[(vec![0, 0], 1.0), (vec![1, 1], 1.0), (vec![2, 2], 1.0)], &[3, 3]).unwrap();
+    assert_tensor_eq(&t, &Tensor::eye(3));
+    assert!(Tensor::<f64>::from_coo(&[(vec![3, 0], 1.0)], &[3, 3]).is_err());
+    assert!(Tensor::<f64>::from_coo(&[(vec![0], 1.0)], &[3, 3]).is_err());
+}
+
+#[test]
+fn from_tensors_stacks_equal_shapes() {
+    let parts: Vec<Tensor<f64>> = (0..3).map(|i| Tensor::from_shape(i as f64, &[2, 2])).collect();
+    let b = Tensor::from_tensors(&parts).unwrap();
+    assert_eq!(b.shape(), &[3, 2, 2]);
+    assert_eq!(b.get_value(&[2, 1, 1]).unwrap(), 2.0);
+    assert_eq!(Tensor::try_from_tensors(&parts).unwrap().shape(), &[3, 2, 2]);
+}
+
+#[test]
+fn try_from_tensors_names_the_offending_index() {
+    let mut parts: Vec<Tensor<f64>> = (0..3).map(|i| Tensor::from_shape(i as f64, &[2, 2])).collect();
+    parts.insert(2, Tensor::ones(&[2, 3]));
+    assert_eq!(Tensor::try_from_tensors(&parts).unwrap_err().message, "tensor 2 has shape [2, 3], expected [2, 2] like tensor 0");
+    assert!(Tensor::from_tensors(&parts).is_err());
+    assert!(Tensor::<f64>::try_from_tensors(&[]).is_err());
+}
+
+#[test]
+fn empty_like_is_contiguous() {
+    let v = Tensor::<f64>::ones(&[3, 4]).transpose(0, 1).unwrap();
+    let e = v.empty_like();
+    assert_eq!(e.shape(), &[4, 3]);
+    assert!(e.is_contiguous());
+    assert_eq!(e.memory_bytes(), 96);
+    assert_eq!(Tensor::<u8>::empty(&[2, 5]).size(), 10);
+}
+
+#[test]
+fn from_vec3_builds_a_cube() {
+    let t = Tensor::from_vec3(&[vec![vec![1u32, 2], vec![3, 4]], vec![vec![5, 6], vec![7, 8]]]).unwrap();
+    assert_tensor_eq(&t, &Tensor::from_iter(1..9).reshape(&[2, 2, 2]).unwrap());
+    assert_eq!(Tensor::<u32>::from_vec3(&[]).unwrap().shape(), &[0, 0, 0]);
+    assert_tensor_eq(&Tensor::from_vec2(&[vec![1u32, 2], vec![3, 4]]).unwrap(), &Tensor::from_iter(1..5).reshape(&[2, 2]).unwrap());
+}
+
+#[test]
+fn ragged_input_is_an_error() {
+    let e = Tensor::from_vec3(&[vec![vec![1u32, 2], vec![3, 4]], vec![vec![5, 6], vec![7]]]).unwrap_err();
+    assert_eq!(e.message, "row [1][1] has length 1, expected 2");
+    assert_eq!(Tensor::from_vec3(&[vec![vec![1u32]], vec![]]).unwrap_err().message, "[1] has 0 rows, expected 1");
+    assert!(Tensor::from_vec2(&[vec![1u32, 2], vec![3]]).is_err());
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "csv")]
+
+use std::fs;
+use std::path::Path;
+
+use crabtorch::Tensor;
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn csv_round_trip() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("crabtorch_csv_round_trip_in.csv");
+    fs::write(&input, "a,b,c\n1,2,3\n4.5, -6 ,7\n").unwrap();
+    let t = Tensor::from_csv(&input, true).unwrap();
+    assert_tensor_eq(&t, &Tensor::from_array(&[1.0, 2.0, 3.0, 4.5, -6.0, 7.0]).reshape(&[2, 3]).unwrap());
+    let output = dir.join("crabtorch_csv_round_trip_out.csv");
+    t.to_csv(&output).unwrap();
+    assert_tensor_eq(&Tensor::from_csv(&output, false).unwrap(), &t);
+}
+
+#[test]
+fn csv_errors() {
+    let path = std::env::temp_dir().join("crabtorch_csv_errors.csv");
+    fs::write(&path, "1,2\n3\n").unwrap();
+    assert!(Tensor::from_csv(&path, false).is_err());
+    assert!(Tensor::from_csv(Path::new("/nonexistent/tensor.csv"), false).is_err());
+    assert!(Tensor::from_array(&[1.0]).to_csv(&path).is_err());
+}
@@ -0,0 +1,29 @@
+use crabtorch::Tensor;
+
+#[test]
+fn precision_aligns_the_columns() {
+    let a = Tensor::from_array(&[1.0, -20.5, 3.14285, 400.0]).reshape(&[2, 2]).unwrap();
+    assert_eq!(format!("{:.2}", a), "[[  1.00, -20.50]\n [  3.14, 400.00]]");
+    assert_eq!(format!("{:.1}", Tensor::scalar(2.0)), "2.0");
+}
+
+#[test]
+fn without_a_precision_nothing_changes() {
+    let a = Tensor::from_array(&[1.0, -20.5, 3.14285, 400.0]).reshape(&[2, 2]).unwrap();
+    assert_eq!(format!("{}", a), "[[1, -20.5]\n [3.14285, 400]]");
+    assert_eq!(format!("{}", Tensor::from_array(&[1u32, 20])), "[1, 20]");
+    assert_eq!(format!("{}", Tensor::<f64>::default()), "");
+}
+
+#[test]
+fn debug_shows_the_layout() {
+    let a: Tensor<u32> = Tensor::from_fn(&[3, 4], |i| (i[0] * 4 + i[1]) as u32);
+    assert_eq!(
+        format!("{:?}", a.transpose(0, 1).unwrap()),
+        "Tensor { shape: [4, 3], strides: [1, 4], base_index: 0, contiguous: false, data: [0, 4, 8, 1, 5, 9, 2, 6, ..] }"
+    );
+    assert_eq!(
+        format!("{:?}", a.get(&[1]).unwrap()),
+        "Tensor { shape: [4], strides: [1], base_index: 4, contiguous: true, data: [4, 5, 6, 7] }"
+    );
+}
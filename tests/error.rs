@@ -0,0 +1,30 @@
+use std::error::Error;
+
+use crabtorch::TensorError;
+
+#[test]
+fn source_and_display() {
+    let plain = TensorError::new("outer");
+    assert!(plain.source().is_none());
+    let chained = TensorError::new("outer").with_source("inner");
+    assert_eq!(chained.source().unwrap().to_string(), "inner");
+    assert_eq!(chained.to_string(), "outer: inner");
+    assert_eq!(format!("{:?}", chained), "outer: inner");
+}
+
+#[test]
+fn boxes_as_a_std_error() {
+    let boxed: Box<dyn Error + Send + Sync> = Box::new(TensorError::new("y"));
+    assert_eq!(boxed.to_string(), "y");
+}
+
+#[cfg(feature = "csv")]
+#[test]
+fn malformed_csv_keeps_the_parse_error() {
+    let path = std::env::temp_dir().join("crabtorch_error_source.csv");
+    std::fs::write(&path, "1,2\n3,abc\n").unwrap();
+    let err = crabtorch::Tensor::from_csv(&path, false).unwrap_err();
+    assert_eq!(err.message, "could not parse \"abc\" at row 1, column 1");
+    assert!(err.source().unwrap().downcast_ref::<std::num::ParseFloatError>().is_some());
+    assert_eq!(err.to_string(), "could not parse \"abc\" at row 1, column 1: invalid float literal");
+}
@@ -0,0 +1,18 @@
+use crabtorch::Tensor;
+use crabtorch::expr::Expr;
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn fused_pipeline_matches_separate_calls() {
+    let t = Tensor::from_array(&[-3.0, -0.25, 0.0, 2.0]).reshape(&[2, 2]).unwrap().transpose(0, 1).unwrap();
+    let fused = Expr::new(&t).mul_scalar(2.0).add_scalar(1.0).relu().eval();
+    assert_tensor_eq(&fused, &t.mul_scalar(2.0).add_scalar(1.0).relu());
+    assert!(fused.is_contiguous());
+}
+
+#[test]
+fn neg_and_map_in_a_pipeline() {
+    let u: Tensor<i64> = Tensor::from_fn(&[3], |i| i[0] as i64);
+    assert_tensor_eq(&(-Expr::new(&u).sub_scalar(1)).div_scalar(1).map(|x| x * x).eval(), &Tensor::from_array(&[1, 0, 1]));
+    assert_tensor_eq(&Expr::new(&u).eval(), &u);
+}
@@ -0,0 +1,136 @@
+use crabtorch::Tensor;
+use crabtorch::tensor::{Activation, ZeroSumPolicy};
+use crabtorch::testing::{assert_tensor_close, assert_tensor_eq};
+
+#[test]
+fn interp_linear_resamples() {
+    assert_eq!(Tensor::from_array(&[0.0, 1.0]).interp_linear(3).unwrap().into_iter().collect::<Vec<_>>(), vec![0.0, 0.5, 1.0]);
+    assert_eq!(Tensor::from_array(&[0.0, 2.0, 4.0]).interp_linear(2).unwrap().into_iter().collect::<Vec<_>>(), vec![0.0, 4.0]);
+    assert!(Tensor::from_array(&[0.0]).interp_linear(3).is_err());
+}
+
+#[test]
+fn gradient_of_a_linspace_is_constant() {
+    let x = Tensor::linspace(0.0, 1.0, 5);
+    assert_tensor_close(&x.gradient(0, 0.2).unwrap(), &Tensor::from_shape(1.0, &[5]), 1e-12);
+    assert!(Tensor::from_array(&[1.0]).gradient(0, 1.0).is_err());
+}
+
+#[test]
+fn gradient_along_each_axis() {
+    let y = Tensor::from_array(&[1.0, 2.0, 4.0, 7.0, 11.0, 16.0]).reshape(&[2, 3]).unwrap();
+    assert_tensor_close(&y.gradient(1, 1.0).unwrap(), &Tensor::from_array(&[1.0, 1.5, 2.0, 4.0, 4.5, 5.0]).reshape(&[2, 3]).unwrap(), 1e-12);
+    assert_tensor_close(&y.gradient(0, 2.0).unwrap(), &Tensor::from_array(&[3.0, 4.5, 6.0, 3.0, 4.5, 6.0]).reshape(&[2, 3]).unwrap(), 1e-12);
+}
+
+#[test]
+fn clip_grad_norm_scales_down_only() {
+    let clipped = Tensor::from_array(&[6.0, 8.0]).clip_grad_norm(5.0);
+    assert_tensor_close(&clipped, &Tensor::from_array(&[3.0, 4.0]), 1e-12);
+    let small = Tensor::from_array(&[1.2, 1.6]);
+    assert_tensor_close(&small.clip_grad_norm(5.0), &small, 0.0);
+}
+
+#[test]
+fn dropout_mask_is_reproducible() {
+    let a = Tensor::dropout_mask(&[100, 100], 0.3, 42).unwrap();
+    assert_tensor_eq(&a, &Tensor::dropout_mask(&[100, 100], 0.3, 42).unwrap());
+    let dropped = a.clone().into_iter().filter(|&x| x == 0.0).count() as f64 / 10000.0;
+    assert!((dropped - 0.3).abs() < 0.02, "{} were dropped", dropped);
+    assert!(a.into_iter().all(|x| x == 0.0 || (x - 1.0 / 0.7).abs() < 1e-12));
+}
+
+#[test]
+fn dropout_mask_checks_p() {
+    assert!(Tensor::dropout_mask(&[2], 1.0, 0).is_err());
+    assert!(Tensor::dropout_mask(&[2], -0.1, 0).is_err());
+    assert_tensor_eq(&Tensor::dropout_mask(&[3], 0.0, 1).unwrap(), &Tensor::ones(&[3]));
+}
+
+#[test]
+fn normalize_sum_along_an_axis() {
+    let a = Tensor::from_array(&[1.0, 3.0]);
+    assert_tensor_close(&a.normalize_sum(0, ZeroSumPolicy::Error).unwrap(), &Tensor::from_array(&[0.25, 0.75]), 1e-12);
+    let z = Tensor::from_array(&[1.0, 3.0, 0.0, 0.0]).reshape(&[2, 2]).unwrap();
+    assert_tensor_close(&z.normalize_sum(0, ZeroSumPolicy::Error).unwrap(), &Tensor::from_array(&[1.0, 1.0, 0.0, 0.0]).reshape(&[2, 2]).unwrap(), 1e-12);
+}
+
+#[test]
+fn normalize_sum_zero_sum_policies() {
+    let z = Tensor::from_array(&[1.0, 3.0, 0.0, 0.0]).reshape(&[2, 2]).unwrap();
+    assert!(z.normalize_sum(1, ZeroSumPolicy::default()).is_err());
+    assert_tensor_close(&z.normalize_sum(1, ZeroSumPolicy::Uniform).unwrap(), &Tensor::from_array(&[0.25, 0.75, 0.5, 0.5]).reshape(&[2, 2]).unwrap(), 1e-12);
+}
+
+#[test]
+fn nan_and_inf_checks() {
+    let a = Tensor::from_array(&[1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+    assert!(a.has_nan() && a.has_inf() && !a.is_finite());
+    let b = Tensor::from_array(&[1.0, 2.0]);
+    assert!(!b.has_nan() && !b.has_inf() && b.is_finite());
+    let m = Tensor::from_array(&[1.0, 2.0, f64::NAN, 3.0]).reshape(&[2, 2]).unwrap();
+    assert!(!m.get(&[0]).unwrap().has_nan());
+    assert!(m.transpose(0, 1).unwrap().get(&[0]).unwrap().has_nan());
+}
+
+#[test]
+fn nan_to_num_replaces_each_kind() {
+    let a = Tensor::from_array(&[1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+    assert_eq!(a.nan_to_num(0.0, 9.0, -9.0).into_iter().collect::<Vec<_>>(), vec![1.0, 0.0, 9.0, -9.0]);
+}
+
+#[test]
+fn moving_average_windows() {
+    let a = Tensor::from_array(&[1.0, 2.0, 3.0, 4.0]);
+    assert_tensor_close(&a.moving_average(2).unwrap(), &Tensor::from_array(&[1.5, 2.5, 3.5]), 1e-12);
+    assert_tensor_close(&a.moving_average(4).unwrap(), &Tensor::from_array(&[2.5]), 1e-12);
+    assert_tensor_close(&a.moving_average(1).unwrap(), &a, 1e-12);
+}
+
+#[test]
+fn moving_average_checks_the_window() {
+    let a = Tensor::from_array(&[1.0, 2.0, 3.0, 4.0]);
+    assert!(a.moving_average(0).is_err());
+    assert!(a.moving_average(5).is_err());
+    assert!(Tensor::<f64>::ones(&[2, 2]).moving_average(1).is_err());
+}
+
+fn activate(a: Activation) -> Vec<f64> {
+    Tensor::from_array(&[-1.0, 0.0, 1.0, 1000.0]).activate(a).into_iter().collect()
+}
+
+#[test]
+fn activations_against_known_values() {
+    assert_eq!(activate(Activation::Relu), vec![0.0, 0.0, 1.0, 1000.0]);
+    assert_eq!(activate(Activation::Identity), vec![-1.0, 0.0, 1.0, 1000.0]);
+    let s = activate(Activation::Sigmoid);
+    assert!((s[0] - 0.2689414213699951).abs() < 1e-12 && s[1] == 0.5 && s[3] == 1.0);
+    let t = activate(Activation::Tanh);
+    assert!(t[1] == 0.0 && (t[2] - 0.7615941559557649).abs() < 1e-12);
+    let g = activate(Activation::Gelu);
+    assert!(g[1] == 0.0 && (g[2] - 0.8411919906082768).abs() < 1e-9 && (g[0] + 0.15880800939172324).abs() < 1e-9);
+    let sp = activate(Activation::Softplus);
+    assert!((sp[1] - 2f64.ln()).abs() < 1e-12 && (sp[2] - 1.3132616875182228).abs() < 1e-12 && sp[3] == 1000.0);
+    assert_eq!(Activation::default(), Activation::Identity);
+}
+
+#[test]
+fn softplus_is_stable_for_large_negative_inputs() {
+    assert!(Tensor::from_array(&[-1000.0]).softplus().into_iter().all(|y| y >= 0.0 && y.is_finite()));
+}
+
+#[test]
+fn content_hash_follows_logical_content() {
+    let a = Tensor::from_array(&[1.0, 2.0, 3.0, 4.0]).reshape(&[2, 2]).unwrap();
+    let b = Tensor::from_array(&[1.0, 3.0, 2.0, 4.0]).reshape(&[2, 2]).unwrap().transpose(0, 1).unwrap();
+    assert_eq!(a.content_hash(), b.content_hash());
+    assert_eq!(a.content_hash(), a.deep_clone().content_hash());
+    assert_ne!(a.content_hash(), a.reshape(&[4]).unwrap().content_hash());
+    assert_ne!(a.content_hash(), a.transpose(0, 1).unwrap().content_hash());
+}
+
+#[test]
+fn content_hash_uses_the_bit_patterns() {
+    assert_ne!(Tensor::from_array(&[0.0]).content_hash(), Tensor::from_array(&[-0.0]).content_hash());
+    assert_eq!(Tensor::from_array(&[f64::NAN]).content_hash(), Tensor::from_array(&[f64::NAN]).content_hash());
+}
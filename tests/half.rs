@@ -0,0 +1,29 @@
+#![cfg(feature = "half")]
+
+use half::{bf16, f16};
+
+use crabtorch::Tensor;
+use crabtorch::tensor::NanPolicy;
+
+#[test]
+fn f16_relu_then_cast_to_f32() {
+    let t = Tensor::from_array(&[f16::from_f32(-1.5), f16::from_f32(2.0)]);
+    let r: Tensor<f32> = t.relu().cast();
+    assert_eq!(r.into_iter().collect::<Vec<_>>(), vec![0.0, 2.0]);
+}
+
+#[test]
+fn half_types_can_be_random() {
+    assert_eq!(Tensor::<f16>::rand(&[2, 2]).shape(), &[2, 2]);
+    assert!(Tensor::<bf16>::rand(&[4]).relu().into_iter().all(|x| x >= bf16::ZERO));
+}
+
+#[test]
+fn nan_policies_work_for_f16() {
+    let t = Tensor::from_array(&[f16::from_f32(1.0), f16::NAN, f16::from_f32(2.0)]);
+    assert!(t.max(NanPolicy::Propagate).unwrap().is_nan());
+    assert_eq!(t.max(NanPolicy::Smallest).unwrap(), f16::from_f32(2.0));
+    assert_eq!(t.min(NanPolicy::Largest).unwrap(), f16::from_f32(1.0));
+    let sorted: Vec<f16> = t.sort_axis(0, NanPolicy::default()).unwrap().into_iter().collect();
+    assert!(sorted[0] == f16::from_f32(1.0) && sorted[2].is_nan());
+}
@@ -0,0 +1,17 @@
+use crabtorch::prelude::*;
+
+#[test]
+fn prelude_brings_in_the_common_types() {
+    let t: Tensor<i32> = Tensor::from_iter(0..4);
+    let err: Option<TensorError> = t.reshape(&[3]).err();
+    assert!(err.is_some());
+    assert_eq!(t.sum_axis(Axis(0)).unwrap().get_value(&[]).unwrap(), 6);
+}
+
+#[test]
+fn builds_a_reshaped_tensor_from_outside_the_crate() -> Result<(), TensorError> {
+    let t = Tensor::from_iter(0..100).reshape(&[50, 2])?;
+    assert_eq!(t.shape(), &[50, 2]);
+    assert_eq!(t.get_value(&[49, 1])?, 99);
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use crabtorch::Tensor;
+use crabtorch::testing::{assert_tensor_close, assert_tensor_eq};
+
+#[test]
+fn tensordot_reproduces_matmul() {
+    let a = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    let b = Tensor::from_iter(0..12).reshape(&[3, 4]).unwrap();
+    assert_tensor_eq(&a.tensordot(&b, (&[1], &[0])).unwrap(), &a.matmul(&b).unwrap());
+    let bt = b.transpose(0, 1).unwrap();
+    assert_tensor_eq(&a.tensordot(&bt, (&[1], &[1])).unwrap(), &a.matmul(&b).unwrap());
+}
+
+#[test]
+fn tensordot_over_several_axes() {
+    let a = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    assert_eq!(a.tensordot(&a, (&[0, 1], &[0, 1])).unwrap().sum(), (0..6).map(|x| x * x).sum::<i32>());
+    let c = Tensor::from_iter(0..24).reshape(&[2, 3, 4]).unwrap();
+    let b = Tensor::from_iter(0..12).reshape(&[3, 4]).unwrap();
+    assert_eq!(c.tensordot(&b, (&[1], &[0])).unwrap().shape(), &[2, 4, 4]);
+    assert!(a.tensordot(&b, (&[0], &[0])).is_err());
+}
+
+#[test]
+fn cholesky_reconstructs_the_matrix() {
+    let a = Tensor::from_array(&[4.0, 2.0, 2.0, 3.0]).reshape(&[2, 2]).unwrap();
+    let l = a.cholesky().unwrap();
+    assert_eq!(l.get_value(&[0, 1]).unwrap(), 0.0);
+    assert_tensor_close(&l, &Tensor::from_array(&[2.0, 0.0, 1.0, 2f64.sqrt()]).reshape(&[2, 2]).unwrap(), 1e-12);
+    assert_tensor_close(&l.matmul(&l.transpose(0, 1).unwrap()).unwrap(), &a, 1e-12);
+}
+
+#[test]
+fn cholesky_rejects_non_spd_input() {
+    assert!(Tensor::from_array(&[1.0, 2.0, 2.0, 1.0]).reshape(&[2, 2]).unwrap().cholesky().is_err());
+    assert!(Tensor::from_array(&[1.0, 2.0]).cholesky().is_err());
+}
+
+#[test]
+fn solve_finds_the_known_solution() {
+    // Needs a row swap, since the first pivot is zero
+    let a = Tensor::from_array(&[0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0]).reshape(&[3, 3]).unwrap();
+    let x = Tensor::from_array(&[1.0, -2.0, 3.0]);
+    let b = a.matmul(&x.reshape(&[3, 1]).unwrap()).unwrap();
+    assert_tensor_close(&a.solve(&b).unwrap(), &x.reshape(&[3, 1]).unwrap(), 1e-12);
+    assert_tensor_close(&a.solve(&b.reshape(&[3]).unwrap()).unwrap(), &x, 1e-12);
+}
+
+#[test]
+fn lu_factors_reproduce_the_permuted_matrix() {
+    let a = Tensor::from_array(&[0.0, 2.0, 1.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0]).reshape(&[3, 3]).unwrap();
+    let (lu, perm) = a.lu().unwrap();
+    let l = Tensor::from_fn(&[3, 3], |i| if i[0] == i[1] { 1.0 } else if i[0] > i[1] { lu.get_value(i).unwrap() } else { 0.0 });
+    let u = lu.triu(0).unwrap();
+    let pa = Tensor::from_fn(&[3, 3], |i| a.get_value(&[perm.get_value(&[i[0]]).unwrap(), i[1]]).unwrap());
+    assert_tensor_close(&l.matmul(&u).unwrap(), &pa, 1e-12);
+}
+
+#[test]
+fn solve_rejects_singular_and_mismatched_systems() {
+    let singular = Tensor::from_array(&[1.0, 2.0, 2.0, 4.0]).reshape(&[2, 2]).unwrap();
+    assert!(singular.solve(&Tensor::from_array(&[1.0, 1.0])).is_err());
+    assert!(Tensor::<f64>::eye(3).solve(&Tensor::from_array(&[1.0, 1.0])).is_err());
+}
+
+#[test]
+fn det_of_small_matrices() {
+    let a = Tensor::from_array(&[4.0, 7.0, 2.0, 6.0]).reshape(&[2, 2]).unwrap();
+    assert!((a.det().unwrap() - 10.0).abs() < 1e-12);
+    let swap = Tensor::from_array(&[0.0, 1.0, 1.0, 0.0]).reshape(&[2, 2]).unwrap();
+    assert!((swap.det().unwrap() + 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn inv_times_the_matrix_is_the_identity() {
+    let a = Tensor::from_array(&[4.0, 7.0, 2.0, 6.0]).reshape(&[2, 2]).unwrap();
+    assert_tensor_close(&a.inv().unwrap().matmul(&a).unwrap(), &Tensor::eye(2), 1e-12);
+    assert!(Tensor::from_array(&[1.0, 2.0, 2.0, 4.0]).reshape(&[2, 2]).unwrap().inv().is_err());
+}
+
+#[test]
+fn conv1d_is_a_correlation() {
+    let x = Tensor::from_array(&[1.0, 2.0, 3.0, 4.0]);
+    assert_eq!(x.conv1d(&Tensor::from_array(&[1.0, 0.0]), 1, 0).unwrap().into_iter().collect::<Vec<_>>(), vec![1.0, 2.0, 3.0]);
+    assert_eq!(x.conv1d(&Tensor::from_array(&[1.0, 1.0]), 2, 1).unwrap().into_iter().collect::<Vec<_>>(), vec![1.0, 5.0, 4.0]);
+    assert!(x.conv1d(&Tensor::from_array(&[1.0; 5]), 1, 0).is_err());
+}
+
+#[test]
+fn pad_a_transposed_view() {
+    let p = Tensor::from_iter(1..5).reshape(&[2, 2]).unwrap().transpose(0, 1).unwrap().pad(&[(1, 0), (0, 1)], 0).unwrap();
+    assert_eq!(format!("{}", p), "[[0, 0, 0]\n [1, 3, 0]\n [2, 4, 0]]");
+}
+
+#[test]
+fn triu_and_tril() {
+    let ones = Tensor::<u32>::ones(&[3, 3]);
+    assert_eq!(format!("{}", ones.triu(0).unwrap()), "[[1, 1, 1]\n [0, 1, 1]\n [0, 0, 1]]");
+    assert_eq!(format!("{}", ones.tril(-1).unwrap()), "[[0, 0, 0]\n [1, 0, 0]\n [1, 1, 0]]");
+    assert!(Tensor::<u32>::ones(&[3]).triu(0).is_err());
+}
+
+#[test]
+fn pairwise_dist_by_hand() {
+    let a = Tensor::from_array(&[0.0, 0.0, 1.0, 1.0]).reshape(&[2, 2]).unwrap();
+    let b = Tensor::from_array(&[3.0, 4.0]).reshape(&[1, 2]).unwrap();
+    let d = a.pairwise_dist(&b).unwrap();
+    assert_eq!(d.shape(), &[2, 1]);
+    assert_eq!(d.get_value(&[0, 0]).unwrap(), 5.0);
+    assert!((d.get_value(&[1, 0]).unwrap() - 13f64.sqrt()).abs() < 1e-12);
+    assert!(a.pairwise_dist(&Tensor::zeros(&[1, 3])).is_err());
+}
+
+#[test]
+fn matmul_broadcasts_a_matrix_over_the_batch() {
+    let a: Tensor<f64> = Tensor::from_fn(&[5, 2, 3], |i| (i[0] * 6 + i[1] * 3 + i[2]) as f64);
+    let b: Tensor<f64> = Tensor::from_fn(&[3, 4], |i| (i[0] * 4 + i[1]) as f64 - 3.0);
+    let c = a.matmul(&b).unwrap();
+    assert_eq!(c.shape(), &[5, 2, 4]);
+    for k in 0..5 {
+        assert_tensor_eq(&c.get(&[k]).unwrap(), &a.get(&[k]).unwrap().matmul(&b).unwrap());
+    }
+}
+
+#[test]
+fn matmul_with_several_batch_dimensions() {
+    let a: Tensor<f64> = Tensor::from_fn(&[2, 1, 3, 4], |i| (i[0] * 12 + i[2] * 4 + i[3]) as f64);
+    let b: Tensor<f64> = Tensor::from_fn(&[4, 5], |i| (i[0] + i[1]) as f64);
+    let c = a.matmul(&b).unwrap();
+    assert_eq!(c.shape(), &[2, 1, 3, 5]);
+    assert_tensor_eq(&c.get(&[1, 0]).unwrap(), &a.get(&[1, 0]).unwrap().matmul(&b).unwrap());
+    let x: Tensor<f64> = Tensor::from_fn(&[2, 1, 2, 2], |i| (i[0] + i[2] + i[3]) as f64);
+    let y: Tensor<f64> = Tensor::from_fn(&[3, 2, 2], |i| (i[0] * i[1] + i[2]) as f64);
+    let z = x.matmul(&y).unwrap();
+    assert_eq!(z.shape(), &[2, 3, 2, 2]);
+    assert_tensor_eq(&z.get(&[1, 2]).unwrap(), &x.get(&[1, 0]).unwrap().matmul(&y.get(&[2]).unwrap()).unwrap());
+}
+
+#[test]
+fn matmul_rejects_mismatched_shapes() {
+    let a: Tensor<f64> = Tensor::ones(&[2, 1, 3, 4]);
+    assert!(a.matmul(&Tensor::ones(&[3, 5])).is_err());
+    assert!(Tensor::<f64>::ones(&[2, 2, 2]).matmul(&Tensor::ones(&[3, 2, 2])).is_err());
+    assert!(Tensor::<f64>::ones(&[3]).matmul(&Tensor::ones(&[3, 1])).is_err());
+}
+
+#[test]
+fn bmm_needs_matching_batches() {
+    assert_eq!(Tensor::<f64>::ones(&[3, 2, 4]).bmm(&Tensor::ones(&[3, 4, 1])).unwrap().shape(), &[3, 2, 1]);
+    assert!(Tensor::<f64>::ones(&[3, 2, 4]).bmm(&Tensor::ones(&[4, 1])).is_err());
+}
+
+#[test]
+fn attention_scores_by_hand() {
+    // d = 4, so every dot product is halved
+    let q = Tensor::from_array(&[1.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0]).reshape(&[1, 2, 4]).unwrap();
+    let k = Tensor::from_array(&[1.0, 1.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 4.0]).reshape(&[1, 3, 4]).unwrap();
+    let scores = Tensor::attention_scores(&q, &k).unwrap();
+    assert_tensor_close(&scores, &Tensor::from_array(&[0.5, 1.0, 0.0, 1.0, 0.0, 0.0]).reshape(&[1, 2, 3]).unwrap(), 1e-12);
+    let qq = Tensor::ones(&[2, 3, 4]);
+    assert_eq!(Tensor::attention_scores(&qq, &qq).unwrap().shape(), &[2, 3, 3]);
+}
+
+#[test]
+fn attention_scores_check_shapes() {
+    let q = Tensor::ones(&[1, 2, 4]);
+    assert!(Tensor::attention_scores(&q, &Tensor::ones(&[1, 3, 5])).is_err());
+    assert!(Tensor::attention_scores(&q, &Tensor::ones(&[2, 3, 4])).is_err());
+}
+
+#[test]
+fn frobenius_inner_with_itself_is_the_sum_of_squares() {
+    let a = Tensor::from_array(&[1.0, -2.0, 3.0, 4.0, 0.5, 6.0]).reshape(&[2, 3]).unwrap();
+    let squares = a.map(|x| x * x).sum();
+    assert_eq!(a.frobenius_inner(&a).unwrap(), squares);
+    let t = a.transpose(0, 1).unwrap();
+    assert_eq!(t.frobenius_inner(&t.contiguous()).unwrap(), squares);
+    assert!(a.frobenius_inner(&t).is_err());
+}
+
+#[test]
+fn rot90_quarter_turns() {
+    let m = Tensor::from_vec2(&[vec![1u32, 2], vec![3, 4]]).unwrap();
+    assert_tensor_eq(&m.rot90(1).unwrap(), &Tensor::from_vec2(&[vec![2, 4], vec![1, 3]]).unwrap());
+    assert_tensor_eq(&m.rot90(2).unwrap(), &Tensor::from_vec2(&[vec![4, 3], vec![2, 1]]).unwrap());
+    assert_tensor_eq(&m.rot90(-1).unwrap(), &Tensor::from_vec2(&[vec![3, 1], vec![4, 2]]).unwrap());
+    assert_tensor_eq(&m.rot90(3).unwrap(), &m.rot90(-1).unwrap());
+    assert_tensor_eq(&m.rot90(4).unwrap(), &m);
+    assert!(Tensor::from_array(&[1u32]).rot90(1).is_err());
+}
+
+#[test]
+fn rot90_and_flip_on_a_wide_matrix() {
+    let r = Tensor::from_vec2(&[vec![1u32, 2, 3], vec![4, 5, 6]]).unwrap();
+    assert_tensor_eq(&r.rot90(1).unwrap(), &Tensor::from_vec2(&[vec![3, 6], vec![2, 5], vec![1, 4]]).unwrap());
+    assert_tensor_eq(&r.flip(0).unwrap(), &Tensor::from_vec2(&[vec![4, 5, 6], vec![1, 2, 3]]).unwrap());
+}
@@ -0,0 +1,217 @@
+use crabtorch::Tensor;
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn scalar_on_the_left() {
+    assert_tensor_eq(&(3.0 * Tensor::<f64>::ones(&[2, 2])), &Tensor::from_shape(3.0, &[2, 2]));
+    assert_tensor_eq(&(3.0f32 * Tensor::<f32>::ones(&[2, 2])), &Tensor::from_shape(3.0, &[2, 2]));
+    assert_tensor_eq(&(1u8 + Tensor::<u8>::zeros(&[3])), &Tensor::from_shape(1, &[3]));
+    assert_tensor_eq(&(10i32 - Tensor::from_iter(0..3)), &Tensor::from_array(&[10, 9, 8]));
+    assert_tensor_eq(&(12i8 / Tensor::from_array(&[1i8, 2, 3])), &Tensor::from_array(&[12, 6, 4]));
+}
+
+#[test]
+fn scalar_on_the_left_of_a_reference() {
+    let t = Tensor::from_array(&[1.0, 2.0, 4.0]);
+    assert_tensor_eq(&(2.0 * &t), &Tensor::from_array(&[2.0, 4.0, 8.0]));
+    assert_tensor_eq(&(1.0 + &t), &Tensor::from_array(&[2.0, 3.0, 5.0]));
+    assert_tensor_eq(&(1.0 - &t), &Tensor::from_array(&[0.0, -1.0, -3.0]));
+    assert_tensor_eq(&(8.0 / &t), &Tensor::from_array(&[8.0, 4.0, 2.0]));
+    assert_tensor_eq(&(10u32 - &Tensor::from_iter(0..3)), &Tensor::from_array(&[10, 9, 8]));
+    assert_tensor_eq(&t, &Tensor::from_array(&[1.0, 2.0, 4.0]));
+}
+
+#[test]
+fn constructors_work_for_every_primitive() {
+    assert_tensor_eq(&Tensor::<i8>::eye(2), &Tensor::from_array(&[1, 0, 0, 1]).reshape(&[2, 2]).unwrap());
+    assert_tensor_eq(&Tensor::<usize>::zeros(&[2]), &Tensor::from_shape(0, &[2]));
+    assert_tensor_eq(&Tensor::<f32>::ones(&[2, 2]).tril(0).unwrap(), &Tensor::from_array(&[1.0, 0.0, 1.0, 1.0]).reshape(&[2, 2]).unwrap());
+}
+
+#[test]
+fn neg_by_value_and_by_reference() {
+    let t = Tensor::from_array(&[1, -2, 3]);
+    assert_eq!((-&t).into_iter().collect::<Vec<_>>(), vec![-1, 2, -3]);
+    assert_eq!((-t).into_iter().collect::<Vec<_>>(), vec![-1, 2, -3]);
+}
+
+#[test]
+fn rem_and_floor_div() {
+    assert_eq!(Tensor::from_iter(0..6).rem_scalar(3).unwrap().into_iter().collect::<Vec<_>>(), vec![0, 1, 2, 0, 1, 2]);
+    let q = Tensor::from_array(&[7, -7, 7, -7]).floor_div(&Tensor::from_array(&[2, 2, -2, -2])).unwrap();
+    assert_eq!(q.into_iter().collect::<Vec<_>>(), vec![3, -4, -4, 3]);
+    let q = Tensor::from_array(&[-7i8, 7]).floor_div_scalar(2).unwrap();
+    assert_eq!(q.into_iter().collect::<Vec<_>>(), vec![-4, 3]);
+}
+
+#[test]
+#[should_panic(expected = "overflow")]
+fn floor_div_of_the_minimum_by_minus_one_panics() {
+    let _ = Tensor::from_array(&[i32::MIN]).floor_div_scalar(-1);
+}
+
+#[test]
+fn division_by_zero_is_an_error() {
+    assert!(Tensor::from_iter(0..6).rem(&Tensor::from_array(&[1, 0])).is_err());
+    assert!(Tensor::from_iter(0..6).floor_div_scalar(0).is_err());
+}
+
+#[test]
+fn bitwise_ops() {
+    assert_eq!(Tensor::from_array(&[0b11, 0b10, 0b01]).bitand_scalar(0b10).into_iter().collect::<Vec<_>>(), vec![0b10, 0b10, 0b00]);
+    assert_eq!(Tensor::from_array(&[1u8, 2]).shl(&Tensor::from_array(&[1u8, 3])).unwrap().into_iter().collect::<Vec<_>>(), vec![2, 16]);
+}
+
+#[test]
+fn add_into_accumulates_in_the_same_buffer() {
+    let step = Tensor::from_array(&[1, 2]);
+    let mut acc = Tensor::from_array(&[0, 0]);
+    for _ in 0..3 {
+        let previous = acc.clone();
+        previous.add_into(&step, &mut acc).unwrap();
+    }
+    assert_eq!(acc.clone().into_iter().collect::<Vec<_>>(), vec![3, 6]);
+    let mut out = Tensor::from_array(&[0, 0]);
+    acc.mul_into(&step, &mut out).unwrap();
+    assert_eq!(out.into_iter().collect::<Vec<_>>(), vec![3, 12]);
+}
+
+#[test]
+fn into_ops_check_the_output_shape() {
+    let mut out = Tensor::from_array(&[0, 0, 0]);
+    assert!(Tensor::from_array(&[1, 2]).add_into(&Tensor::from_array(&[1, 2]), &mut out).is_err());
+}
+
+#[test]
+fn masked_select_compacts() {
+    let t = Tensor::from_array(&[1, 2, 3, 4, 5]);
+    assert_eq!(t.masked_select(&t.map(|x| x > 2)).unwrap().into_iter().collect::<Vec<_>>(), vec![3, 4, 5]);
+}
+
+#[test]
+fn masked_select_broadcasts_the_mask() {
+    let m = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    assert_eq!(m.masked_select(&Tensor::from_array(&[true, false, true])).unwrap().into_iter().collect::<Vec<_>>(), vec![0, 2, 3, 5]);
+    assert!(m.masked_select(&Tensor::from_array(&[true, false])).is_err());
+}
+
+#[test]
+fn select_scalar_picks_a_branch_per_element() {
+    let mask = Tensor::from_array(&[true, false, true, false]);
+    assert_tensor_eq(&Tensor::select_scalar(&mask, 1u32, 0), &Tensor::from_array(&[1, 0, 1, 0]));
+}
+
+#[test]
+fn masked_select_or_keeps_the_shape() {
+    let a = Tensor::from_array(&[5u32, 6, 7, 8, 9, 10]).reshape(&[2, 3]).unwrap();
+    let mask = Tensor::from_array(&[true, false, true]);
+    assert_tensor_eq(&a.masked_select_or(&mask, 0).unwrap(), &Tensor::from_array(&[5, 0, 7, 8, 0, 10]).reshape(&[2, 3]).unwrap());
+    assert!(a.masked_select_or(&Tensor::from_array(&[true, false]), 0).is_err());
+}
+
+#[test]
+fn saturating_and_wrapping_u8() {
+    let a = Tensor::from_array(&[250u8, 10]);
+    let b = Tensor::from_array(&[10u8, 10]);
+    assert_tensor_eq(&a.add_saturating(&b).unwrap(), &Tensor::from_array(&[255, 20]));
+    assert_tensor_eq(&a.add_wrapping(&b).unwrap(), &Tensor::from_array(&[4, 20]));
+    assert_tensor_eq(&a.mul_saturating(&b).unwrap(), &Tensor::from_array(&[255, 100]));
+    assert_tensor_eq(&a.mul_wrapping(&b).unwrap(), &Tensor::from_array(&[196, 100]));
+}
+
+#[test]
+fn saturating_clamps_at_the_signed_minimum() {
+    let a = Tensor::from_array(&[-100i8]);
+    assert_tensor_eq(&a.add_saturating(&Tensor::from_array(&[-100i8, 0])).unwrap(), &Tensor::from_array(&[-128, -100]));
+}
+
+#[test]
+fn eq_broadcast_against_a_scalar() {
+    let a = Tensor::from_array(&[1u32, 2, 3]);
+    assert_tensor_eq(&a.eq_broadcast(&Tensor::scalar(2)).unwrap(), &Tensor::from_array(&[false, true, false]));
+    assert!(a.eq_broadcast(&Tensor::from_array(&[1, 2])).is_err());
+}
+
+#[test]
+fn for_each_mut_is_seen_by_shared_views() {
+    let a = Tensor::from_array(&[1u32, 2, 3, 4]).reshape(&[2, 2]).unwrap();
+    let row = a.get(&[1]).unwrap();
+    a.for_each_mut(|x| *x *= 2);
+    assert_tensor_eq(&row, &Tensor::from_array(&[6, 8]));
+}
+
+#[test]
+fn for_each_mut_walks_in_logical_order() {
+    let a = Tensor::from_array(&[2u32, 4, 6, 8]).reshape(&[2, 2]).unwrap();
+    let mut order = vec![];
+    let mut n = 0;
+    a.transpose(0, 1).unwrap().for_each_mut(|x| {
+        order.push(*x);
+        n += 1;
+        *x += n;
+    });
+    assert_eq!(order, vec![2, 6, 4, 8]);
+    assert_tensor_eq(&a, &Tensor::from_array(&[3, 7, 8, 12]).reshape(&[2, 2]).unwrap());
+}
+
+#[test]
+fn add_outer_rank_one_update() {
+    let z: Tensor<f64> = Tensor::zeros(&[2, 2]);
+    let r = z.add_outer(&Tensor::from_array(&[1.0, 2.0]), &Tensor::from_array(&[3.0, 4.0])).unwrap();
+    assert_tensor_eq(&r, &Tensor::from_array(&[3.0, 4.0, 6.0, 8.0]).reshape(&[2, 2]).unwrap());
+    assert!(z.add_outer(&Tensor::from_array(&[1.0]), &Tensor::from_array(&[3.0, 4.0])).is_err());
+}
+
+#[test]
+fn add_outer_on_a_transposed_matrix() {
+    let o: Tensor<f64> = Tensor::ones(&[3, 2]);
+    let r = o.transpose(0, 1).unwrap().add_outer(&Tensor::from_array(&[1.0, 0.0]), &Tensor::from_array(&[1.0, 2.0, 3.0])).unwrap();
+    assert_tensor_eq(&r, &Tensor::from_array(&[2.0, 3.0, 4.0, 1.0, 1.0, 1.0]).reshape(&[2, 3]).unwrap());
+}
+
+#[test]
+fn in_place_scalar_ops_chain() {
+    let t: Tensor<u32> = Tensor::from_iter(0..4);
+    let view = t.reshape(&[2, 2]).unwrap();
+    t.mul_scalar_(2).add_scalar_(1);
+    assert_tensor_eq(&t, &Tensor::from_array(&[1, 3, 5, 7]));
+    assert_tensor_eq(&view.get(&[1]).unwrap(), &Tensor::from_array(&[5, 7]));
+}
+
+#[test]
+fn in_place_scalar_ops_update_a_broadcast_element_once() {
+    let s = Tensor::scalar(10u32);
+    s.broadcast_to(&[3, 2]).unwrap().sub_scalar_(1).div_scalar_(3);
+    assert_eq!(s.get_value(&[]).unwrap(), 3);
+}
+
+#[test]
+fn in_place_scalar_ops_update_overlapping_windows_once() {
+    let windows = Tensor::from_parts(vec![0u32, 10, 20, 30], vec![3, 2], vec![1, 1], 0).unwrap();
+    windows.add_scalar_(1);
+    assert_tensor_eq(&windows, &Tensor::from_array(&[1, 11, 11, 21, 21, 31]).reshape(&[3, 2]).unwrap());
+    let base: Tensor<u32> = Tensor::from_iter(0..5);
+    base.unfold(0, 3, 1).unwrap().mul_scalar_(2);
+    assert_tensor_eq(&base, &Tensor::from_array(&[0, 2, 4, 6, 8]));
+}
+
+#[test]
+fn eq_on_contiguous_and_strided_tensors() {
+    let a: Tensor<u32> = Tensor::from_fn(&[300, 200], |i| (i[0] * 7 + i[1] * 13) as u32 % 101);
+    let b = a.deep_clone();
+    assert!(a == b);
+    let t = a.transpose(0, 1).unwrap();
+    assert!(t == t.contiguous());
+    assert!(t != a);
+    assert!(a.get(&[5]).unwrap() == b.get(&[5]).unwrap());
+    assert!(a.get(&[5]).unwrap() != b.get(&[6]).unwrap());
+    b.set_value(&[299, 199], 1000).unwrap();
+    assert!(a != b);
+}
+
+#[test]
+fn eq_compares_shapes_and_values() {
+    assert!(Tensor::from_array(&[1.0, f64::NAN]) != Tensor::from_array(&[1.0, f64::NAN]));
+    assert!(Tensor::scalar(2u32).broadcast_to(&[3]).unwrap() == Tensor::from_array(&[2, 2, 2]));
+    assert!(Tensor::from_array(&[1u32, 2]) != Tensor::from_array(&[1u32, 2]).reshape(&[2, 1]).unwrap());
+}
@@ -0,0 +1,46 @@
+use crabtorch::Tensor;
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn rearrange_permutes_and_merges() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    assert_tensor_eq(&a.rearrange("h w -> w h").unwrap(), &a.transpose(0, 1).unwrap());
+    assert_tensor_eq(&a.rearrange("h w -> (h w)").unwrap(), &a.ravel());
+    assert_tensor_eq(&a.rearrange("h w -> (w h)").unwrap(), &a.transpose(0, 1).unwrap().ravel());
+}
+
+#[test]
+fn rearrange_with_splits_a_group() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    let v: Tensor<u32> = Tensor::from_iter(0..6);
+    assert_tensor_eq(&v.rearrange_with("(h w) -> h w", &[("h", 2)]).unwrap(), &a);
+    assert_tensor_eq(&v.rearrange_with("(h w) -> w h", &[("w", 3)]).unwrap(), &a.transpose(0, 1).unwrap());
+}
+
+#[test]
+fn rearrange_on_a_strided_view() {
+    let b: Tensor<u32> = Tensor::from_fn(&[2, 3, 4], |i| (i[0] * 12 + i[1] * 4 + i[2]) as u32);
+    let r = b.rearrange("b h w -> h (b w)").unwrap();
+    assert_eq!(r.shape(), &[3, 8]);
+    assert_eq!(r.get_value(&[1, 5]).unwrap(), b.get_value(&[1, 1, 1]).unwrap());
+    assert_eq!(b.rearrange("b h w -> b () h w").unwrap().shape(), &[2, 1, 3, 4]);
+    let t = b.transpose(0, 2).unwrap();
+    let split = t.rearrange_with("(x y) h b -> x y h b", &[("x", 2)]).unwrap();
+    assert_tensor_eq(&split, &t.contiguous().reshape(&[2, 2, 3, 2]).unwrap());
+}
+
+#[test]
+fn rearrange_rejects_bad_patterns() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    for pattern in ["h w", "h -> h", "h w -> h", "h h -> h h", "h (w -> h w", "h w -> h w)", "a-b -> a"] {
+        assert!(a.rearrange(pattern).is_err(), "{} was accepted", pattern);
+    }
+}
+
+#[test]
+fn rearrange_checks_group_sizes() {
+    let v: Tensor<u32> = Tensor::from_iter(0..6);
+    assert!(v.rearrange("(h w) -> h w").is_err());
+    assert!(v.rearrange_with("(h w) -> h w", &[("h", 4)]).is_err());
+    assert!(v.rearrange_with("(h w) -> h w", &[("h", 2), ("w", 2)]).is_err());
+}
@@ -0,0 +1,259 @@
+use std::panic::catch_unwind;
+
+use crabtorch::Tensor;
+use crabtorch::tensor::NanPolicy;
+use crabtorch::testing::{assert_tensor_close, assert_tensor_eq};
+
+#[test]
+fn apply_along_axis_with_a_median() {
+    let t = Tensor::from_iter([5, 1, 4, 2, 3, 9, 8, 7, 6, 5, 0, 0, 1, 0, 0, 3, 3, 3, 3, 3]).reshape(&[4, 5]).unwrap();
+    let median = t.apply_along_axis(1, |row| {
+        let mut sorted = row.to_vec();
+        sorted.sort();
+        sorted[sorted.len() / 2]
+    }).unwrap();
+    assert_eq!(median.into_iter().collect::<Vec<_>>(), vec![3, 7, 0, 3]);
+    let column_sums = t.apply_along_axis(0, |col| col.iter().sum::<i32>()).unwrap();
+    assert_eq!(column_sums.into_iter().collect::<Vec<_>>(), vec![17, 12, 15, 11, 11]);
+    assert!(t.apply_along_axis(2, |s| s.len()).is_err());
+}
+
+#[test]
+fn max_pool1d() {
+    let t = Tensor::from_array(&[1, 3, 2, 4]);
+    assert_eq!(t.max_pool1d(2, 2).unwrap().into_iter().collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!(t.max_pool1d(2, 1).unwrap().into_iter().collect::<Vec<_>>(), vec![3, 3, 4]);
+    assert!(t.max_pool1d(5, 1).is_err());
+}
+
+#[test]
+fn avg_pool1d_pools_the_last_axis() {
+    let t = Tensor::from_array(&[1.0, 3.0, 2.0, 4.0, 5.0, 0.0, 0.0, 2.0, 2.0, 6.0]).reshape(&[2, 5]).unwrap();
+    let pooled = t.avg_pool1d(2, 2).unwrap();
+    assert_eq!(pooled.shape(), &[2, 2]);
+    assert_eq!(pooled.into_iter().collect::<Vec<_>>(), vec![2.0, 3.0, 0.0, 2.0]);
+}
+
+#[test]
+fn unfold_drops_a_partial_window() {
+    let u = Tensor::from_iter(0..5).unfold(0, 2, 2).unwrap();
+    assert_eq!(format!("{}", u), "[[0, 1]\n [2, 3]]");
+}
+
+#[test]
+fn for_each_ref_visits_in_logical_order() {
+    let t = Tensor::from_iter(1u32..7).reshape(&[2, 3]).unwrap().transpose(0, 1).unwrap();
+    let mut seen = vec![];
+    t.for_each_ref(|x| seen.push(*x));
+    assert_eq!(seen, vec![1, 4, 2, 5, 3, 6]);
+    assert_eq!(t.sum(), 21);
+    assert_eq!(t.product(), 720);
+}
+
+#[test]
+fn for_each_ref_does_not_clone() {
+    let t = Tensor::from_iter(vec![String::from("ab"), String::from("c")]);
+    let mut total = 0;
+    t.for_each_ref(|s| total += s.len());
+    assert_eq!(total, 3);
+}
+
+#[test]
+fn max_and_min_under_each_nan_policy() {
+    let t = Tensor::from_array(&[1.0, f64::NAN, 2.0]);
+    assert!(t.max(NanPolicy::Propagate).unwrap().is_nan());
+    assert!(t.min(NanPolicy::Propagate).unwrap().is_nan());
+    assert_eq!(t.max(NanPolicy::Smallest).unwrap(), 2.0);
+    assert!(t.min(NanPolicy::Smallest).unwrap().is_nan());
+    assert!(t.max(NanPolicy::Largest).unwrap().is_nan());
+    assert_eq!(t.min(NanPolicy::Largest).unwrap(), 1.0);
+}
+
+#[test]
+fn sort_axis_places_nans_by_policy() {
+    let t = Tensor::from_array(&[1.0, f64::NAN, 2.0]);
+    let sorted: Vec<f64> = t.sort_axis(0, NanPolicy::Smallest).unwrap().into_iter().collect();
+    assert!(sorted[0].is_nan() && sorted[1] == 1.0 && sorted[2] == 2.0);
+    let sorted: Vec<f64> = t.sort_axis(0, NanPolicy::Propagate).unwrap().into_iter().collect();
+    assert!(sorted[0] == 1.0 && sorted[1] == 2.0 && sorted[2].is_nan());
+    let m = Tensor::from_array(&[3.0, 1.0, 2.0, 0.0, 5.0, 4.0]).reshape(&[2, 3]).unwrap();
+    assert_eq!(format!("{}", m.sort_axis(0, NanPolicy::default()).unwrap()), "[[0, 1, 2]\n [3, 5, 4]]");
+    assert_eq!(format!("{}", m.sort_axis(1, NanPolicy::default()).unwrap()), "[[1, 2, 3]\n [0, 4, 5]]");
+}
+
+#[test]
+fn nan_policies_work_for_f32() {
+    let t = Tensor::from_array(&[1.0f32, f32::NAN, 2.0]);
+    assert!(t.max(NanPolicy::Propagate).unwrap().is_nan());
+    assert!(t.min(NanPolicy::Propagate).unwrap().is_nan());
+    assert_eq!(t.max(NanPolicy::Smallest).unwrap(), 2.0f32);
+    assert_eq!(t.min(NanPolicy::Largest).unwrap(), 1.0f32);
+    let sorted: Vec<f32> = t.sort_axis(0, NanPolicy::Smallest).unwrap().into_iter().collect();
+    assert!(sorted[0].is_nan() && sorted[1] == 1.0 && sorted[2] == 2.0);
+}
+
+#[test]
+fn topk_values_and_indices() {
+    let (values, indices) = Tensor::from_array(&[1, 5, 3, 2]).topk(2, 0, true).unwrap();
+    assert_eq!(values.into_iter().collect::<Vec<_>>(), vec![5, 3]);
+    assert_eq!(indices.into_iter().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn topk_smallest_along_an_axis() {
+    let m = Tensor::from_array(&[3, 1, 2, 0, 5, 4]).reshape(&[2, 3]).unwrap();
+    let (values, indices) = m.topk(1, 0, false).unwrap();
+    assert_eq!(values.shape(), &[1, 3]);
+    assert_eq!(values.into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(indices.into_iter().collect::<Vec<_>>(), vec![1, 0, 0]);
+    assert!(m.topk(4, 1, true).is_err());
+}
+
+#[test]
+fn sum_axes_matches_chained_sums() {
+    let t = Tensor::from_iter(0..24).reshape(&[2, 3, 4]).unwrap();
+    let s = t.sum_axes(&[0, 2], false).unwrap();
+    assert_eq!(s.shape(), &[3]);
+    assert_tensor_eq(&s, &t.sum_axis(2).unwrap().sum_axis(0).unwrap());
+    assert_eq!(t.sum_axes(&[2, 0], true).unwrap().shape(), &[1, 3, 1]);
+    assert_eq!(t.sum_axes(&[], false).unwrap().shape(), &[2, 3, 4]);
+}
+
+#[test]
+fn max_and_mean_over_axes() {
+    let t = Tensor::from_iter(0..24).reshape(&[2, 3, 4]).unwrap();
+    assert_eq!(t.max_axes(&[1, 2], false).unwrap().into_iter().collect::<Vec<_>>(), vec![11, 23]);
+    let f = Tensor::from_array(&[1.0, 2.0, 3.0, 5.0]).reshape(&[2, 2]).unwrap();
+    assert_eq!(f.mean_axes(&[0], false).unwrap().into_iter().collect::<Vec<_>>(), vec![2.0, 3.5]);
+}
+
+#[test]
+fn mean_over_an_empty_dimension_is_an_error() {
+    let t: Tensor<i32> = Tensor::zeros(&[2, 0]);
+    assert!(t.mean_axes(&[1], false).is_err());
+    assert!(t.max_axes(&[1], false).is_err());
+    assert!(Tensor::<f64>::zeros(&[0, 3]).mean_axes(&[0], true).is_err());
+}
+
+#[test]
+fn sum_axes_rejects_bad_axes() {
+    let t = Tensor::from_iter(0..24).reshape(&[2, 3, 4]).unwrap();
+    assert!(t.sum_axes(&[0, 0], false).is_err());
+    assert!(t.sum_axes(&[3], false).is_err());
+}
+
+#[test]
+fn sum_and_product_of_an_iterator_of_tensors() {
+    let v: Vec<Tensor<f64>> = (1..4).map(|i| Tensor::from_shape(i as f64, &[2, 2])).collect();
+    assert_tensor_eq(&v.iter().sum::<Tensor<f64>>(), &Tensor::from_shape(6.0, &[2, 2]));
+    assert_tensor_eq(&v.into_iter().product::<Tensor<f64>>(), &Tensor::from_shape(6.0, &[2, 2]));
+    assert_eq!(Vec::<Tensor<f64>>::new().into_iter().sum::<Tensor<f64>>().size(), 0);
+}
+
+#[test]
+fn summing_mismatched_shapes_panics() {
+    let result = catch_unwind(|| vec![Tensor::from_shape(1.0, &[2]), Tensor::from_shape(1.0, &[1])].into_iter().sum::<Tensor<f64>>());
+    assert!(result.is_err());
+}
+
+#[test]
+fn cummax_and_cummin() {
+    let a = Tensor::from_array(&[1u32, 3, 2, 5, 4]);
+    assert_tensor_eq(&a.cummax(0).unwrap(), &Tensor::from_array(&[1, 3, 3, 5, 5]));
+    assert_tensor_eq(&a.cummin(0).unwrap(), &Tensor::from_array(&[1, 1, 1, 1, 1]));
+    assert!(a.cummax(1).is_err());
+}
+
+#[test]
+fn cumulative_along_either_axis() {
+    let m = Tensor::from_array(&[3u32, 1, 2, 0, 5, 1]).reshape(&[2, 3]).unwrap();
+    assert_tensor_eq(&m.cummin(1).unwrap(), &Tensor::from_array(&[3, 1, 1, 0, 0, 0]).reshape(&[2, 3]).unwrap());
+    assert_tensor_eq(&m.cummax(0).unwrap(), &Tensor::from_array(&[3, 1, 2, 3, 5, 2]).reshape(&[2, 3]).unwrap());
+}
+
+#[test]
+fn bincount_counts_each_value() {
+    let a = Tensor::from_array(&[0usize, 1, 1, 2, 2, 2]);
+    assert_tensor_eq(&a.bincount(0).unwrap(), &Tensor::from_array(&[1, 2, 3]));
+    assert_tensor_eq(&a.bincount(5).unwrap(), &Tensor::from_array(&[1, 2, 3, 0, 0]));
+    assert_tensor_eq(&Tensor::<usize>::default().bincount(2).unwrap(), &Tensor::from_array(&[0, 0]));
+    assert!(a.reshape(&[2, 3]).unwrap().bincount(0).is_err());
+}
+
+#[test]
+fn quantile_interpolates() {
+    let a = Tensor::from_array(&[4.0, 1.0, 3.0, 2.0]);
+    assert_eq!(a.quantile(0.5).unwrap(), 2.5);
+    assert_eq!(a.quantile(0.0).unwrap(), 1.0);
+    assert_eq!(a.quantile(0.25).unwrap(), 1.75);
+    assert_eq!(a.quantile(1.0).unwrap(), 4.0);
+    assert!(Tensor::from_array(&[1.0, f64::NAN]).quantile(0.5).unwrap().is_nan());
+}
+
+#[test]
+fn quantile_errors() {
+    assert!(Tensor::from_array(&[1.0]).quantile(1.5).is_err());
+    assert!(Tensor::<f64>::default().quantile(0.5).is_err());
+}
+
+#[test]
+fn quantile_axis() {
+    let m = Tensor::from_array(&[1.0, 5.0, 3.0, 2.0, 4.0, 6.0]).reshape(&[2, 3]).unwrap();
+    assert_tensor_close(&m.quantile_axis(0.5, 1).unwrap(), &Tensor::from_array(&[3.0, 4.0]), 1e-12);
+    assert_tensor_close(&m.quantile_axis(0.5, 0).unwrap(), &Tensor::from_array(&[1.5, 4.5, 4.5]), 1e-12);
+}
+
+#[test]
+fn masked_sum_and_mean() {
+    let a = Tensor::from_array(&[1u32, 2, 3, 4]);
+    let mask = Tensor::from_array(&[true, false, true, false]);
+    assert_eq!(a.sum_masked(&mask).unwrap(), 4);
+    assert_eq!(a.mean_masked(&mask).unwrap(), 2.0);
+    let b: Tensor<f64> = Tensor::from_fn(&[2, 4], |i| (i[0] * 4 + i[1]) as f64);
+    assert_eq!(b.sum_masked(&mask).unwrap(), 0.0 + 2.0 + 4.0 + 6.0);
+}
+
+#[test]
+fn masked_reductions_check_the_mask() {
+    let a = Tensor::from_array(&[1u32, 2, 3, 4]);
+    assert!(a.sum_masked(&Tensor::from_array(&[true])).is_ok());
+    assert!(a.sum_masked(&Tensor::from_array(&[true, false])).is_err());
+    assert!(a.mean_masked(&Tensor::from_array(&[false])).is_err());
+}
+
+#[test]
+fn max_with_index_points_at_the_values() {
+    let a = Tensor::from_array(&[3u32, 9, 1, 7, 2, 7]).reshape(&[2, 3]).unwrap();
+    let (values, indices) = a.max_with_index_axis(1).unwrap();
+    assert_tensor_eq(&values, &Tensor::from_array(&[9, 7]));
+    assert_tensor_eq(&indices, &Tensor::from_array(&[1, 0]));
+    for r in 0..2 {
+        assert_eq!(a.get_value(&[r, indices.get_value(&[r]).unwrap()]).unwrap(), values.get_value(&[r]).unwrap());
+    }
+}
+
+#[test]
+fn min_with_index_along_axis_0() {
+    let a = Tensor::from_array(&[3u32, 9, 1, 7, 2, 7]).reshape(&[2, 3]).unwrap();
+    let (values, indices) = a.min_with_index_axis(0).unwrap();
+    assert_tensor_eq(&values, &Tensor::from_array(&[3, 2, 1]));
+    assert_tensor_eq(&indices, &Tensor::from_array(&[0, 1, 0]));
+    assert!(a.max_with_index_axis(2).is_err());
+}
+
+#[test]
+fn with_index_over_empty_dimensions() {
+    let e: Tensor<u32> = Tensor::from_fn(&[2, 0], |_| 0);
+    assert!(e.max_with_index_axis(1).is_err());
+    assert_eq!(e.max_with_index_axis(0).unwrap().0.shape(), &[0]);
+}
+
+#[test]
+fn diff_along_each_axis() {
+    assert_tensor_eq(&Tensor::from_array(&[1u32, 2, 4, 7]).diff(0).unwrap(), &Tensor::from_array(&[1, 2, 3]));
+    let m = Tensor::from_array(&[1i64, 4, 9, 2, 2, 0]).reshape(&[2, 3]).unwrap();
+    assert_tensor_eq(&m.diff(1).unwrap(), &Tensor::from_array(&[3, 5, 0, -2]).reshape(&[2, 2]).unwrap());
+    assert_tensor_eq(&m.diff(0).unwrap(), &Tensor::from_array(&[1, -2, -9]).reshape(&[1, 3]).unwrap());
+    assert!(Tensor::from_array(&[1u32]).diff(0).is_err());
+    assert!(m.diff(2).is_err());
+}
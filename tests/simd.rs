@@ -0,0 +1,22 @@
+#![cfg(feature = "simd")]
+
+use wide::f32x8;
+
+use crabtorch::Tensor;
+
+#[test]
+fn map_simd_matches_map() {
+    // Not a multiple of the lane count, so the tail goes through the padded path
+    let a: Tensor<f32> = Tensor::from_fn(&[1003], |i| i[0] as f32 - 500.0);
+    let simd = a.map_simd(|v| v.max(f32x8::ZERO) * f32x8::splat(0.5));
+    let scalar = a.map(|x| x.max(0.0) * 0.5);
+    assert_eq!(simd.shape(), &[1003]);
+    assert_eq!(simd.into_iter().collect::<Vec<_>>(), scalar.into_iter().collect::<Vec<_>>());
+}
+
+#[test]
+fn map_simd_on_a_strided_view() {
+    let m: Tensor<f32> = Tensor::from_fn(&[5, 3], |i| (i[0] * 3 + i[1]) as f32);
+    let t = m.transpose(0, 1).unwrap();
+    assert_eq!(t.map_simd(|v| v + f32x8::ONE).into_iter().collect::<Vec<_>>(), t.map(|x| x + 1.0).into_iter().collect::<Vec<_>>());
+}
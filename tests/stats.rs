@@ -0,0 +1,61 @@
+use crabtorch::Tensor;
+use crabtorch::tensor::{MinMaxScaler, RunningStats};
+use crabtorch::testing::assert_tensor_close;
+
+#[test]
+fn running_stats_match_the_batch_statistics() {
+    let batches = [
+        Tensor::from_array(&[1.0, 2.0, 3.0]),
+        Tensor::from_array(&[4.0, 10.0]).reshape(&[2, 1]).unwrap(),
+        Tensor::scalar(5.0)
+    ];
+    let mut stats = RunningStats::new();
+    for batch in &batches {
+        stats.update(batch);
+    }
+    let all: Vec<f64> = batches.iter().flat_map(|b| b.into_iter()).collect();
+    let mean = all.iter().sum::<f64>() / all.len() as f64;
+    let var = all.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / all.len() as f64;
+    assert_eq!(stats.count(), 6);
+    assert!((stats.mean() - mean).abs() < 1e-12);
+    assert!((stats.var() - var).abs() < 1e-12);
+    assert!((stats.std() - var.sqrt()).abs() < 1e-12);
+}
+
+#[test]
+fn running_stats_are_nan_before_any_update() {
+    let stats = RunningStats::<f64>::default();
+    assert_eq!(stats.count(), 0);
+    assert!(stats.mean().is_nan());
+    assert!(stats.var().is_nan());
+}
+
+#[test]
+fn quantize_round_trips_within_half_a_step() {
+    let t = Tensor::from_array(&[-1.0, 0.0, 0.3, 2.5]).reshape(&[2, 2]).unwrap();
+    let (q, scale, zero_point) = t.quantize_auto();
+    let back = q.dequantize(scale, zero_point);
+    assert_eq!(back.shape(), &[2, 2]);
+    for (x, y) in t.into_iter().zip(&back) {
+        assert!((x - y).abs() <= scale / 2.0 + 1e-12, "{} came back as {}", x, y);
+    }
+}
+
+#[test]
+fn min_max_scaler_over_two_batches() {
+    let mut scaler = MinMaxScaler::new();
+    scaler.partial_fit(&Tensor::from_array(&[0.0, 10.0, 5.0, 2.0, 20.0, 5.0]).reshape(&[2, 3]).unwrap()).unwrap();
+    scaler.partial_fit(&Tensor::from_array(&[4.0, 15.0, 5.0]).reshape(&[1, 3]).unwrap()).unwrap();
+    let out = scaler.transform(&Tensor::from_array(&[1.0, 12.5, 5.0, 4.0, 20.0, 7.0]).reshape(&[2, 3]).unwrap()).unwrap();
+    // The last feature never varied, so it maps to 0
+    assert_tensor_close(&out, &Tensor::from_array(&[0.25, 0.25, 0.0, 1.0, 1.0, 0.0]).reshape(&[2, 3]).unwrap(), 1e-12);
+}
+
+#[test]
+fn min_max_scaler_checks_its_input() {
+    let mut scaler = MinMaxScaler::new();
+    assert!(scaler.transform(&Tensor::ones(&[1, 3])).is_err());
+    scaler.partial_fit(&Tensor::ones(&[2, 3])).unwrap();
+    assert!(scaler.partial_fit(&Tensor::ones(&[1, 2])).is_err());
+    assert!(scaler.transform(&Tensor::ones(&[3])).is_err());
+}
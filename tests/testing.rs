@@ -0,0 +1,38 @@
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use crabtorch::Tensor;
+use crabtorch::testing::{assert_tensor_close, assert_tensor_eq};
+
+fn panic_message<F: FnOnce()>(f: F) -> String {
+    let err = catch_unwind(AssertUnwindSafe(f)).unwrap_err();
+    err.downcast_ref::<String>().unwrap().clone()
+}
+
+#[test]
+fn assert_tensor_eq_passes_on_equal_tensors() {
+    let a = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    assert_tensor_eq(&a, &a.deep_clone());
+}
+
+#[test]
+fn assert_tensor_eq_reports_the_first_differing_index() {
+    let a = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    let b = Tensor::from_iter([0, 1, 2, 3, 9, 5]).reshape(&[2, 3]).unwrap();
+    let message = panic_message(|| assert_tensor_eq(&a, &b));
+    assert_eq!(message, "tensors differ at index [1, 1]: left 4, right 9");
+}
+
+#[test]
+fn assert_tensor_eq_reports_a_shape_mismatch() {
+    let a = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    let message = panic_message(|| assert_tensor_eq(&a, &a.reshape(&[3, 2]).unwrap()));
+    assert_eq!(message, "tensor shapes differ: left [2, 3], right [3, 2]");
+}
+
+#[test]
+fn assert_tensor_close_allows_the_tolerance() {
+    assert_tensor_close(&Tensor::from_array(&[1.0, f64::INFINITY]), &Tensor::from_array(&[1.0000001, f64::INFINITY]), 1e-3);
+    let message = panic_message(|| assert_tensor_close(&Tensor::from_array(&[1.0]), &Tensor::from_array(&[1.1]), 1e-3));
+    assert!(message.starts_with("tensors differ at index [0] by more than 0.001"), "{}", message);
+    assert!(catch_unwind(|| assert_tensor_close(&Tensor::from_array(&[f64::NAN]), &Tensor::from_array(&[f64::NAN]), 1.0)).is_err());
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "trace")]
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use crabtorch::Tensor;
+
+// Collects everything the subscriber writes so the test can look at it
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn ops_emit_trace_events() {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, || {
+        let a = Tensor::from_array(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).reshape(&[2, 3]).unwrap();
+        let b: Tensor<f64> = Tensor::ones(&[3, 1]);
+        a.matmul(&b).unwrap().sum();
+        a.sum_axis(0).unwrap();
+    });
+    let out = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+    assert!(out.contains("op=\"matmul\" inputs=[[2, 3], [3, 1]] output=[2, 1]"), "{}", out);
+    assert!(out.contains("op=\"reshape\""));
+    assert!(out.contains("op=\"sum\""));
+    assert!(out.contains("op=\"reduce\""));
+}
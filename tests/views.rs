@@ -0,0 +1,373 @@
+use crabtorch::{Axis, Tensor};
+use crabtorch::tensor::{Layout, broadcast_shapes};
+use crabtorch::testing::assert_tensor_eq;
+
+#[test]
+fn broadcast_dim_reads_the_same_element_repeatedly() {
+    let a = Tensor::from_iter(0..3).reshape(&[3, 1]).unwrap();
+    let b = a.broadcast_dim(1, 4).unwrap();
+    assert_eq!(b.shape(), &[3, 4]);
+    assert_eq!(b.strides(), &[1, 0]);
+    assert_eq!(b.size(), 12);
+    assert_eq!(format!("{}", b), "[[0, 0, 0, 0]\n [1, 1, 1, 1]\n [2, 2, 2, 2]]");
+    assert_eq!(b.into_iter().collect::<Vec<_>>(), vec![0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2]);
+}
+
+#[test]
+fn broadcast_dim_requires_a_size_one_dimension() {
+    let a = Tensor::from_iter(0..3).reshape(&[3, 1]).unwrap();
+    assert!(a.broadcast_dim(0, 2).is_err());
+    assert!(a.broadcast_dim(2, 2).is_err());
+}
+
+#[test]
+fn view_needs_a_contiguous_tensor() {
+    let t = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    let tt = t.transpose(0, 1).unwrap();
+    assert!(tt.view(&[6]).is_err());
+    assert!(tt.reshape(&[6]).is_err());
+    assert_eq!(tt.contiguous().view(&[6]).unwrap().into_iter().collect::<Vec<_>>(), vec![0, 3, 1, 4, 2, 5]);
+    assert_eq!(t.get(&[1]).unwrap().view(&[3, 1]).unwrap().shape(), &[3, 1]);
+}
+
+#[test]
+fn shape_array_checks_the_rank() {
+    let t = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    let [rows, cols] = t.shape_array().unwrap();
+    assert_eq!((rows, cols), (2, 3));
+    assert!(t.reshape(&[1, 2, 3]).unwrap().shape_array::<2>().is_err());
+}
+
+#[test]
+fn views_report_their_layout() {
+    let t = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    assert_eq!(t.get(&[1]).unwrap().base_index(), 3);
+    assert_eq!(t.transpose(0, 1).unwrap().strides(), &[1, 3]);
+    assert_eq!(t.layout(), Layout { shape: vec![2, 3], strides: vec![3, 1], base_index: 0 });
+}
+
+#[test]
+fn raw_parts_round_trip() {
+    let t = Tensor::from_raw_parts(vec![1, 2, 3, 4, 5, 6], vec![2, 3]).unwrap();
+    assert_eq!(t.get_value(&[1, 0]).unwrap(), 4);
+    assert_eq!(t.into_raw_parts().unwrap(), (vec![1, 2, 3, 4, 5, 6], vec![2, 3]));
+    assert!(Tensor::from_raw_parts(vec![1, 2, 3], vec![2, 3]).is_err());
+}
+
+#[test]
+fn into_raw_parts_fails_while_the_buffer_is_shared() {
+    let t = Tensor::from_raw_parts(vec![1, 2, 3], vec![3]).unwrap();
+    let _other = t.clone();
+    assert!(t.into_raw_parts().is_err());
+}
+
+#[test]
+fn set_slice_writes_a_column() {
+    let t = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    t.set_slice(&[0..2, 0..1], &Tensor::from_array(&[10, 20]).reshape(&[2, 1]).unwrap()).unwrap();
+    assert_eq!(format!("{}", t), "[[10, 1, 2]\n [20, 4, 5]]");
+    assert!(t.set_slice(&[0..2, 0..2], &Tensor::from_array(&[10, 20]).reshape(&[2, 1]).unwrap()).is_err());
+    assert!(t.set_slice(&[0..3, 0..3], &Tensor::from_shape(0, &[3, 3])).is_err());
+}
+
+#[test]
+fn set_slice_from_an_overlapping_view() {
+    let t = Tensor::from_iter(0..6).reshape(&[2, 3]).unwrap();
+    t.set_slice(&[0..2, 0..1], &t.slice(&[0..2, 2..3]).unwrap()).unwrap();
+    assert_eq!(format!("{}", t), "[[2, 1, 2]\n [5, 4, 5]]");
+}
+
+#[test]
+fn make_unique_stops_sharing() {
+    let a = Tensor::from_iter(0..4);
+    let mut b = a.clone();
+    b.make_unique();
+    b.set_value(&[0], 9).unwrap();
+    assert_eq!(a.get_value(&[0]).unwrap(), 0);
+    assert_eq!(b.get_value(&[0]).unwrap(), 9);
+}
+
+#[test]
+fn make_unique_keeps_an_unshared_buffer() {
+    let mut c = Tensor::from_iter(0..4);
+    let layout = c.layout();
+    c.make_unique();
+    assert_eq!(c.layout(), layout);
+}
+
+#[test]
+fn transpose_contiguous_matches_transpose_then_contiguous() {
+    // Not a multiple of the block size in either dimension
+    let a: Tensor<u32> = Tensor::from_fn(&[257, 130], |i| (i[0] * 1000 + i[1]) as u32);
+    let t = a.transpose_contiguous().unwrap();
+    assert!(t.is_contiguous());
+    assert_tensor_eq(&t, &a.transpose(0, 1).unwrap().contiguous());
+    assert_tensor_eq(&a.transpose(0, 1).unwrap().transpose_contiguous().unwrap(), &a);
+    assert!(Tensor::<u32>::zeros(&[3]).transpose_contiguous().is_err());
+}
+
+#[test]
+fn enumerate_indexed_yields_coordinates() {
+    let a = Tensor::from_array(&[1u32, 2, 3, 4]).reshape(&[2, 2]).unwrap();
+    let pairs: Vec<_> = a.enumerate_indexed().collect();
+    assert_eq!(pairs, vec![(vec![0, 0], 1), (vec![0, 1], 2), (vec![1, 0], 3), (vec![1, 1], 4)]);
+    let transposed: Vec<_> = a.transpose(0, 1).unwrap().enumerate_indexed().collect();
+    assert_eq!(transposed[1], (vec![0, 1], 3));
+    assert_eq!(Tensor::scalar(7u32).enumerate_indexed().collect::<Vec<_>>(), vec![(vec![], 7)]);
+}
+
+#[test]
+fn array_variants_match_the_slice_versions() {
+    let a: Tensor<u32> = Tensor::from_fn(&[100], |i| i[0] as u32);
+    let r = a.reshape_arr([50, 2]).unwrap();
+    assert_tensor_eq(&r, &a.reshape(&[50, 2]).unwrap());
+    assert_tensor_eq(&r.get_arr([3]).unwrap(), &r.get(&[3]).unwrap());
+    assert_tensor_eq(&r.slice_arr([1..3, 0..1]).unwrap(), &r.slice(&[1..3, 0..1]).unwrap());
+    assert!(a.reshape_arr([3, 3]).is_err());
+}
+
+#[test]
+fn gather_coords_picks_the_diagonal() {
+    let a: Tensor<u32> = Tensor::from_fn(&[3, 3], |i| (i[0] * 3 + i[1]) as u32);
+    assert_tensor_eq(&a.gather_coords(&[&[0, 0], &[1, 1], &[2, 2]]).unwrap(), &Tensor::from_array(&[0, 4, 8]));
+    assert_eq!(a.gather_coords(&[]).unwrap().shape(), &[0]);
+    assert!(a.gather_coords(&[&[0]]).is_err());
+    assert!(a.gather_coords(&[&[0, 3]]).is_err());
+}
+
+#[test]
+fn dtype_name_and_size() {
+    let a = Tensor::from_array(&[1.0f64]);
+    assert_eq!(a.dtype_name(), "f64");
+    assert_eq!(a.dtype_size(), 8);
+    assert_eq!(Tensor::from_array(&[1u8]).dtype_name(), "u8");
+    assert_eq!(Tensor::from_array(&[1u8]).dtype_size(), 1);
+}
+
+#[test]
+fn reinterpret_f32_as_u32_bits() {
+    let a = Tensor::from_array(&[1.0f32, -2.0, 0.5, 0.0]).reshape(&[2, 2]).unwrap();
+    let bits: Tensor<u32> = unsafe { a.reinterpret() }.unwrap();
+    assert_eq!(bits.shape(), &[2, 2]);
+    assert_eq!(bits.into_iter().collect::<Vec<_>>(), vec![1.0f32.to_bits(), (-2.0f32).to_bits(), 0.5f32.to_bits(), 0]);
+}
+
+#[test]
+fn reinterpret_keeps_the_view_and_shares_the_buffer() {
+    let a = Tensor::from_array(&[1.0f32, -2.0, 0.5, 0.0]).reshape(&[2, 2]).unwrap();
+    let t: Tensor<i32> = unsafe { a.transpose(0, 1).unwrap().reinterpret() }.unwrap();
+    assert_eq!(t.get_value(&[1, 0]).unwrap(), (-2.0f32).to_bits() as i32);
+    t.set_value(&[0, 1], 3.0f32.to_bits() as i32).unwrap();
+    assert_eq!(a.get_value(&[1, 0]).unwrap(), 3.0);
+    // The view keeps the buffer alive on its own
+    drop(a);
+    assert_eq!(t.get_value(&[0, 0]).unwrap(), 1.0f32.to_bits() as i32);
+}
+
+#[test]
+fn reinterpret_rejects_a_different_size() {
+    let a = Tensor::from_array(&[1.0f32]);
+    assert!(unsafe { a.reinterpret::<u64>() }.is_err());
+    assert!(unsafe { a.reinterpret::<u16>() }.is_err());
+}
+
+#[test]
+fn reinterpret_rejects_a_different_alignment() {
+    let a = Tensor::from_array(&[1u32, 2]);
+    assert!(unsafe { a.reinterpret::<[u8; 4]>() }.is_err());
+    assert!(unsafe { Tensor::from_array(&[[0u8; 4]]).reinterpret::<f32>() }.is_err());
+}
+
+#[test]
+fn expect_shape_in_a_chain() {
+    let a: Tensor<f64> = Tensor::ones(&[50, 3]);
+    let w: Tensor<f64> = Tensor::ones(&[3, 10]);
+    let r = a.matmul(&w).unwrap();
+    assert_eq!(r.expect_shape(&[50, 10]).unwrap().shape(), &[50, 10]);
+    assert_eq!(r.expect_shape(&[10, 50]).unwrap_err().message, "expected shape [10, 50], got [50, 10]");
+}
+
+#[test]
+fn ravel_copies_only_when_it_has_to() {
+    let a = Tensor::from_array(&[1u32, 2, 3, 4, 5, 6]).reshape(&[2, 3]).unwrap();
+    let t = a.transpose(0, 1).unwrap();
+    assert!(t.flatten().is_err());
+    assert_tensor_eq(&t.ravel(), &Tensor::from_array(&[1, 4, 2, 5, 3, 6]));
+    // A contiguous tensor gives a view, so writes show up in the original
+    a.ravel().set_value(&[0], 9).unwrap();
+    assert_eq!(a.get_value(&[0, 0]).unwrap(), 9);
+    assert_tensor_eq(&a.get(&[1]).unwrap().ravel(), &Tensor::from_array(&[4, 5, 6]));
+    assert_eq!(Tensor::scalar(3u32).ravel().shape(), &[1]);
+}
+
+#[test]
+fn to_array_in_logical_order() {
+    let a: Tensor<i32> = Tensor::from_fn(&[2, 2], |i| (i[0] * 2 + i[1]) as i32 - 1);
+    let arr: [i32; 4] = a.to_array().unwrap();
+    assert_eq!(arr, [-1, 0, 1, 2]);
+    assert_eq!(a.transpose(0, 1).unwrap().to_array::<4>().unwrap(), [-1, 1, 0, 2]);
+    assert_eq!(a.to_array::<3>().unwrap_err().message, "cannot convert a tensor of 4 elements into an array of 3");
+}
+
+#[test]
+fn at_indexes_the_first_dimension() {
+    let a: Tensor<u32> = Tensor::from_iter(0..10);
+    let x = a.at(3).unwrap();
+    assert!(x.is_scalar());
+    assert_eq!(x.get_value(&[]).unwrap(), 3);
+    assert_eq!(a.at_value(7).unwrap(), 7);
+    assert!(a.at(10).is_err());
+    let m = a.reshape(&[2, 5]).unwrap();
+    assert_eq!(m.at(1).unwrap().shape(), &[5]);
+    assert!(m.at_value(1).is_err());
+}
+
+#[test]
+fn views_report_the_whole_buffer() {
+    let a: Tensor<f64> = Tensor::ones(&[4, 5]);
+    let row = a.get(&[2]).unwrap();
+    assert_eq!(a.memory_bytes(), 160);
+    assert_eq!(row.memory_bytes(), 160);
+    assert_eq!(row.logical_bytes(), 40);
+    let b = Tensor::scalar(1u8).broadcast_to(&[100]).unwrap();
+    assert_eq!((b.memory_bytes(), b.logical_bytes()), (1, 100));
+}
+
+#[test]
+fn broadcast_queries() {
+    let a: Tensor<f64> = Tensor::ones(&[3, 1]);
+    let b: Tensor<f64> = Tensor::ones(&[1, 4]);
+    assert!(a.can_broadcast_with(&b));
+    assert_eq!(a.broadcast_shape_with(&b), Some(vec![3, 4]));
+    let c: Tensor<f64> = Tensor::ones(&[3, 2]);
+    let d: Tensor<bool> = Tensor::from_shape(true, &[4, 2]);
+    assert!(!c.can_broadcast_with(&d));
+    assert_eq!(c.broadcast_shape_with(&d), None);
+    assert!(broadcast_shapes(&[3, 2], &[4, 2]).is_err());
+}
+
+#[test]
+fn axis_methods_take_usize_or_axis() {
+    let a: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    assert_tensor_eq(&a.sum_axis(Axis(1)).unwrap(), &a.sum_axis(1).unwrap());
+    assert_tensor_eq(&a.transpose(Axis(0), 1).unwrap(), &a.transpose(0, Axis(1)).unwrap());
+    assert_eq!(Tensor::concat(&[a.clone(), a.clone()], Axis(1)).unwrap().shape(), &[2, 6]);
+    assert_eq!(a.cummax(Axis(0)).unwrap().shape(), &[2, 3]);
+    assert!(a.sum_axis(Axis(2)).is_err());
+    let col = a.slice(&[0..2, 0..1]).unwrap();
+    assert_tensor_eq(&col.broadcast_dim(Axis(1), 2).unwrap(), &col.broadcast_dim(1, 2).unwrap());
+}
+
+#[test]
+fn atleast_adds_leading_dimensions() {
+    let v = Tensor::from_array(&[1u32, 2, 3]);
+    assert_eq!(v.atleast_1d().shape(), &[3]);
+    assert_eq!(v.atleast_2d().shape(), &[1, 3]);
+    assert_eq!(v.atleast_3d().shape(), &[1, 1, 3]);
+    let m: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    assert_eq!(m.atleast_2d().shape(), &[2, 3]);
+    assert_eq!(Tensor::scalar(1u32).atleast_1d().shape(), &[1]);
+}
+
+#[test]
+fn unsqueeze_inserts_a_unit_axis() {
+    let m: Tensor<u32> = Tensor::from_fn(&[2, 3], |i| (i[0] * 3 + i[1]) as u32);
+    let u = m.unsqueeze(1).unwrap();
+    assert_eq!(u.shape(), &[2, 1, 3]);
+    assert_tensor_eq(&u.reshape(&[2, 3]).unwrap(), &m);
+    let t = m.transpose(0, 1).unwrap().unsqueeze(2).unwrap();
+    assert_eq!(t.get_value(&[2, 1, 0]).unwrap(), 5);
+    assert!(m.unsqueeze(3).is_err());
+}
+
+#[test]
+fn dim_names_the_rank_when_out_of_range() {
+    let m: Tensor<f64> = Tensor::ones(&[2, 3]);
+    assert_eq!(m.dims(), 2);
+    assert_eq!(m.dim(1).unwrap(), 3);
+    assert_eq!(m.dim(5).unwrap_err().message, "axis 5 is out of range for a rank 2 tensor");
+}
+
+#[test]
+fn from_parts_with_overlapping_windows() {
+    let w = Tensor::from_parts(vec![1u32, 2, 3, 4, 5], vec![3, 3], vec![1, 1], 0).unwrap();
+    assert_tensor_eq(&w, &Tensor::from_array(&[1, 2, 3, 2, 3, 4, 3, 4, 5]).reshape(&[3, 3]).unwrap());
+    let offset = Tensor::from_parts(vec![0u32, 1, 2, 3, 4, 5, 6], vec![2, 2], vec![2, 1], 3).unwrap();
+    assert_eq!(offset.into_iter().collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+    assert_eq!(Tensor::<u32>::from_parts(vec![], vec![0, 3], vec![3, 1], 0).unwrap().size(), 0);
+}
+
+#[test]
+fn from_parts_checks_the_bounds() {
+    assert!(Tensor::from_parts(vec![1u32, 2, 3, 4, 5], vec![3, 3], vec![1, 2], 0).is_err());
+    assert!(Tensor::from_parts(vec![1u32], vec![1], vec![1, 1], 0).is_err());
+    assert!(Tensor::from_parts(vec![1u32], vec![1], vec![1], 1).is_err());
+}
+
+#[test]
+fn from_parts_checks_the_base_index_of_an_empty_layout() {
+    assert!(Tensor::<u32>::from_parts(vec![], vec![0], vec![1], 5).is_err());
+    assert!(Tensor::from_parts(vec![1u32, 2], vec![0], vec![1], 3).is_err());
+    let a = Tensor::<u32>::from_parts(vec![], vec![0], vec![1], 0).unwrap();
+    let b = Tensor::from_parts(vec![1u32, 2], vec![0], vec![1], 2).unwrap();
+    assert!(a == b);
+}
+
+#[test]
+fn replace_data_is_seen_by_every_view() {
+    let a: Tensor<u32> = Tensor::zeros(&[2, 3]);
+    let row = a.get(&[1]).unwrap();
+    a.replace_data(vec![1, 2, 3, 4, 5, 6]).unwrap();
+    assert_tensor_eq(&a, &Tensor::from_array(&[1, 2, 3, 4, 5, 6]).reshape(&[2, 3]).unwrap());
+    assert_tensor_eq(&row, &Tensor::from_array(&[4, 5, 6]));
+    row.replace_data(vec![0; 6]).unwrap();
+    assert_eq!(a.get_value(&[0, 0]).unwrap(), 0);
+    assert!(a.replace_data(vec![1, 2]).is_err());
+}
+
+#[test]
+fn transpose_inplace_swaps_across_the_diagonal() {
+    let a: Tensor<u32> = Tensor::from_fn(&[3, 3], |i| (i[0] * 3 + i[1]) as u32);
+    let old = a.deep_clone();
+    let row = a.get(&[0]).unwrap();
+    a.transpose_inplace().unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_eq!(a.get_value(&[i, j]).unwrap(), old.get_value(&[j, i]).unwrap());
+        }
+    }
+    assert_tensor_eq(&row, &Tensor::from_array(&[0, 3, 6]));
+}
+
+#[test]
+fn transpose_inplace_on_a_sub_buffer() {
+    let big: Tensor<u32> = Tensor::from_iter(0..27).reshape(&[3, 9]).unwrap();
+    let sub = big.slice(&[1..2, 0..9]).unwrap().reshape(&[3, 3]).unwrap();
+    sub.transpose_inplace().unwrap();
+    assert_eq!(big.get_value(&[1, 1]).unwrap(), 12);
+    assert_eq!(big.get_value(&[0, 1]).unwrap(), 1);
+    assert!(Tensor::<u32>::zeros(&[2, 3]).transpose_inplace().is_err());
+    assert!(sub.transpose(0, 1).unwrap().transpose_inplace().is_err());
+}
+
+#[test]
+fn ravel_and_unravel_round_trip() {
+    let a: Tensor<f64> = Tensor::zeros(&[3, 4, 5]);
+    for flat in 0..60 {
+        assert_eq!(a.ravel_index(&a.unravel_index(flat).unwrap()).unwrap(), flat);
+    }
+    assert_eq!(a.unravel_index(23).unwrap(), vec![1, 0, 3]);
+    assert_eq!(a.ravel_index(&[2, 3, 4]).unwrap(), 59);
+    // Row-major over the shape, whatever the strides
+    assert_eq!(a.transpose(0, 2).unwrap().unravel_index(23).unwrap(), vec![1, 3, 2]);
+}
+
+#[test]
+fn ravel_and_unravel_check_bounds() {
+    let a: Tensor<f64> = Tensor::zeros(&[3, 4, 5]);
+    assert!(a.unravel_index(60).is_err());
+    assert!(a.ravel_index(&[3, 0, 0]).is_err());
+    assert!(a.ravel_index(&[0, 0]).is_err());
+    assert_eq!(Tensor::scalar(1.0).unravel_index(0).unwrap(), Vec::<usize>::new());
+    assert_eq!(Tensor::scalar(1.0).ravel_index(&[]).unwrap(), 0);
+}